@@ -62,6 +62,7 @@ pub fn next_horse_animation(
         let new_animation = match &event.change {
             AnimationChange::Attack
             | AnimationChange::Hit(_)
+            | AnimationChange::Block
             | AnimationChange::Death
             | AnimationChange::Mount
             | AnimationChange::Idle
@@ -125,6 +125,7 @@ pub fn trigger_king_animation(
                     AnimationChange::Idle => KingAnimation::Mount,
                     AnimationChange::Attack => KingAnimation::Mount,
                     AnimationChange::Hit(_) => KingAnimation::Mount,
+                    AnimationChange::Block => KingAnimation::Mount,
                     AnimationChange::Death => KingAnimation::Death,
                     AnimationChange::KnockOut => KingAnimation::KnockOut,
                     AnimationChange::Mount => KingAnimation::Mount,
@@ -134,6 +135,7 @@ pub fn trigger_king_animation(
                     AnimationChange::Idle => KingAnimation::Idle,
                     AnimationChange::Attack => KingAnimation::Attack,
                     AnimationChange::Hit(_) => KingAnimation::Hit,
+                    AnimationChange::Block => KingAnimation::Hit,
                     AnimationChange::Death => KingAnimation::Death,
                     AnimationChange::KnockOut => KingAnimation::KnockOut,
                     AnimationChange::Mount => KingAnimation::Mount,
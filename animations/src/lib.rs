@@ -24,8 +24,8 @@ use shared::{enum_map::*, server::entities::UnitAnimation};
 use sprite_variant_loader::AssetsToLoad;
 use ui::{item_info::ItemInfoSpriteSheet, map_icon::MapIconSpriteSheet};
 use units::{
-    UnitSpriteSheets, set_unit_after_play_once, set_unit_idle, set_unit_sprite_animation,
-    set_unit_walking, trigger_unit_animation,
+    UnitSpriteSheets, hide_ambushing_unit, reveal_ambushing_unit, set_unit_after_play_once,
+    set_unit_idle, set_unit_sprite_animation, set_unit_walking, trigger_unit_animation,
 };
 
 use crate::{
@@ -34,7 +34,7 @@ use crate::{
     sound::{AnimationSound, AnimationSoundPlugin},
     ui::{
         animations::UIAnimationsPlugin, army_formations::FormationIconSpriteSheet,
-        commander_menu::CommanderMenuSpriteSheet,
+        army_orders::OrderIconSpriteSheet, commander_menu::CommanderMenuSpriteSheet,
     },
     world::{road::RoadSpriteSheet, trees::pine::PineTreeSpriteSheet},
 };
@@ -211,6 +211,7 @@ impl Plugin for AnimationPlugin {
         app.init_resource::<ItemInfoSpriteSheet>();
         app.init_resource::<MapIconSpriteSheet>();
         app.init_resource::<FormationIconSpriteSheet>();
+        app.init_resource::<OrderIconSpriteSheet>();
 
         app.init_resource::<BuildingSpriteSheets>();
 
@@ -225,6 +226,8 @@ impl Plugin for AnimationPlugin {
         .add_observer(set_king_walking)
         .add_observer(set_king_idle)
         .add_observer(set_king_after_play_once)
+        .add_observer(hide_ambushing_unit)
+        .add_observer(reveal_ambushing_unit)
         .add_observer(set_unit_walking)
         .add_observer(set_unit_idle)
         .add_observer(set_unit_after_play_once)
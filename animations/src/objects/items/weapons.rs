@@ -39,7 +39,9 @@ impl From<WeaponType> for Weapons {
                 MeleeWeapon::Pike => Weapons::Pike,
             },
             WeaponType::Projectile(p) => match p {
-                ProjectileWeapon::Bow => Weapons::Bow,
+                ProjectileWeapon::Bow | ProjectileWeapon::FireBow | ProjectileWeapon::Bombs => {
+                    Weapons::Bow
+                }
             },
         }
     }
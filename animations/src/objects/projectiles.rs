@@ -6,6 +6,9 @@ use crate::StaticSpriteSheet;
 #[derive(Debug, Clone, Copy, Mappable)]
 pub enum Projectiles {
     Arrow,
+    FireArrow,
+    Bomb,
+    Boulder,
 }
 
 #[derive(Resource)]
@@ -29,6 +32,9 @@ impl FromWorld for ProjectileSpriteSheet {
 
         let parts = EnumMap::new(|c| match c {
             Projectiles::Arrow => 1,
+            Projectiles::FireArrow => 5,
+            Projectiles::Bomb => 11,
+            Projectiles::Boulder => 10,
         });
 
         Self {
@@ -138,7 +138,12 @@ fn play_sound_on_entity_change(
             AnimationChange::Hit(hit_by) => match hit_by {
                 Hitby::Arrow => "animation_sound/arrow/arrow_hits_flesh.ogg",
                 Hitby::Melee => "animation_sound/arrow/arrow_hits_flesh.ogg",
+                Hitby::Explosion => {
+                    "animation_sound/crafting/hammering_&_sawing/hitting_&_chiseling_stone_1.ogg"
+                }
+                Hitby::Status => continue,
             },
+            AnimationChange::Block => "animation_sound/shieldwarrior/sword_hit.ogg",
             AnimationChange::Mount => "animation_sound/horse/horse_sound.ogg",
             _ => continue,
         };
@@ -165,7 +170,10 @@ fn play_animation_on_projectile_spawn(
     let projectile_type = projectile.get_mut(trigger.entity)?;
 
     let sound_handles = match projectile_type {
-        ProjectileType::Arrow => vec![asset_server.load("animation_sound/arrow/arrow_flying.ogg")],
+        ProjectileType::Arrow | ProjectileType::FireArrow => {
+            vec![asset_server.load("animation_sound/arrow/arrow_flying.ogg")]
+        }
+        ProjectileType::Bomb | ProjectileType::Boulder => Vec::new(),
     };
 
     sound_events.write(PlayAnimationSoundEvent {
@@ -186,6 +194,12 @@ fn play_on_interactable(
         InteractionType::Recruit => {
             asset_server.load("animation_sound/recruitment/recruite_call.ogg")
         }
+        InteractionType::SiegeEngine => {
+            asset_server.load("animation_sound/crafting/hammering_&_sawing/hammer_1.ogg")
+        }
+        InteractionType::Gold => {
+            asset_server.load("animation_sound/crafting/inventory_management/pick_up_coins_1.ogg")
+        }
         InteractionType::Flag => todo!(),
         InteractionType::Building => todo!(),
         InteractionType::Mount => todo!(),
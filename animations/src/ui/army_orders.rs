@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+
+use shared::enum_map::*;
+
+use crate::StaticSpriteSheet;
+
+#[derive(Component, PartialEq, Eq, Debug, Clone, Copy, Mappable, Default)]
+pub enum OrderIcons {
+    #[default]
+    FollowFlag,
+    HoldPosition,
+    DefendBuilding,
+    Charge,
+}
+
+#[derive(Resource)]
+pub struct OrderIconSpriteSheet {
+    pub sprite_sheet: StaticSpriteSheet<OrderIcons>,
+}
+
+impl FromWorld for OrderIconSpriteSheet {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let texture: Handle<Image> = asset_server.load("ui/commander/orders.png");
+        let mut texture_atlas_layouts = world.resource_mut::<Assets<TextureAtlasLayout>>();
+
+        let layout = texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
+            UVec2 { x: 50, y: 50 },
+            4,
+            1,
+            None,
+            None,
+        ));
+
+        let parts = EnumMap::new(|c| match c {
+            OrderIcons::FollowFlag => 0,
+            OrderIcons::HoldPosition => 1,
+            OrderIcons::DefendBuilding => 2,
+            OrderIcons::Charge => 3,
+        });
+
+        Self {
+            sprite_sheet: StaticSpriteSheet {
+                texture,
+                layout,
+                parts,
+            },
+        }
+    }
+}
@@ -8,6 +8,7 @@ pub enum CommanderMenuNodes {
     Flag,
     Camp,
     Formation,
+    Orders,
 }
 
 #[derive(Resource)]
@@ -23,7 +24,7 @@ impl FromWorld for CommanderMenuSpriteSheet {
 
         let layout = texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
             UVec2 { x: 50, y: 50 },
-            3,
+            4,
             1,
             None,
             None,
@@ -33,6 +34,7 @@ impl FromWorld for CommanderMenuSpriteSheet {
             CommanderMenuNodes::Flag => 2,
             CommanderMenuNodes::Camp => 0,
             CommanderMenuNodes::Formation => 1,
+            CommanderMenuNodes::Orders => 3,
         });
 
         Self {
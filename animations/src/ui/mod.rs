@@ -1,5 +1,6 @@
 pub mod animations;
 pub mod army_formations;
+pub mod army_orders;
 pub mod commander_menu;
 pub mod item_info;
 pub mod map_icon;
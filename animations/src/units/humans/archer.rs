@@ -38,6 +38,7 @@ pub fn archer(world: &mut World) -> AnimationSpriteSheet<UnitAnimation, SpriteVa
         UnitAnimation::Walk => anim!(1, 5),
         UnitAnimation::Attack => anim!(3, 9),
         UnitAnimation::Hit => anim!(5, 2),
+        UnitAnimation::Block => anim!(5, 2),
         UnitAnimation::Death => anim!(6, 3),
     });
 
@@ -56,6 +57,7 @@ pub fn archer(world: &mut World) -> AnimationSpriteSheet<UnitAnimation, SpriteVa
         }),
         UnitAnimation::Attack => None,
         UnitAnimation::Hit => None,
+        UnitAnimation::Block => None,
         UnitAnimation::Death => None,
     });
 
@@ -38,6 +38,7 @@ pub fn commander(world: &mut World) -> AnimationSpriteSheet<UnitAnimation, Sprit
         UnitAnimation::Walk => anim!(1, 5),
         UnitAnimation::Attack => anim!(3, 5),
         UnitAnimation::Hit => anim!(4, 2),
+        UnitAnimation::Block => anim!(4, 2),
         UnitAnimation::Death => anim!(5, 5),
     });
 
@@ -56,6 +57,7 @@ pub fn commander(world: &mut World) -> AnimationSpriteSheet<UnitAnimation, Sprit
         }),
         UnitAnimation::Attack => None,
         UnitAnimation::Hit => None,
+        UnitAnimation::Block => None,
         UnitAnimation::Death => None,
     });
 
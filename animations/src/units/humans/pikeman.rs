@@ -36,6 +36,7 @@ pub fn pikeman(world: &mut World) -> AnimationSpriteSheet<UnitAnimation, SpriteV
         UnitAnimation::Walk => anim!(1, 5),
         UnitAnimation::Attack => anim!(3, 6),
         UnitAnimation::Hit => anim!(4, 2),
+        UnitAnimation::Block => anim!(4, 2),
         UnitAnimation::Death => anim!(5, 4),
     });
     let animations_sound = EnumMap::new(move |c| match c {
@@ -53,6 +54,7 @@ pub fn pikeman(world: &mut World) -> AnimationSpriteSheet<UnitAnimation, SpriteV
         }),
         UnitAnimation::Attack => None,
         UnitAnimation::Hit => None,
+        UnitAnimation::Block => None,
         UnitAnimation::Death => None,
     });
     AnimationSpriteSheet {
@@ -29,6 +29,7 @@ pub fn shieldwarrior(world: &mut World) -> AnimationSpriteSheet<UnitAnimation, S
         UnitAnimation::Walk => anim!(1, 5),
         UnitAnimation::Attack => anim!(3, 5),
         UnitAnimation::Hit => anim!(5, 2),
+        UnitAnimation::Block => anim!(2, 3),
         UnitAnimation::Death => anim!(6, 3),
     });
 
@@ -40,6 +41,7 @@ pub fn shieldwarrior(world: &mut World) -> AnimationSpriteSheet<UnitAnimation, S
             sound_trigger: AnimationSoundTrigger::EndFrameTimer,
         }),
         UnitAnimation::Hit => None,
+        UnitAnimation::Block => None,
         UnitAnimation::Death => None,
     });
 
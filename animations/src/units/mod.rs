@@ -9,6 +9,7 @@ use shared::{
     enum_map::*,
     networking::UnitType,
     server::{
+        ai::InAmbush,
         entities::{Unit, UnitAnimation},
         physics::movement::Moving,
     },
@@ -57,6 +58,7 @@ pub fn trigger_unit_animation(
             AnimationChange::Idle => UnitAnimation::Idle,
             AnimationChange::Attack => UnitAnimation::Attack,
             AnimationChange::Hit(_) => UnitAnimation::Hit,
+            AnimationChange::Block => UnitAnimation::Block,
             AnimationChange::Death => UnitAnimation::Death,
             AnimationChange::KnockOut => UnitAnimation::Death,
             AnimationChange::Mount => UnitAnimation::Idle,
@@ -72,6 +74,16 @@ pub fn trigger_unit_animation(
     }
 }
 
+pub fn hide_ambushing_unit(trigger: On<Add, InAmbush>, mut commands: Commands) {
+    commands.entity(trigger.entity).insert(Visibility::Hidden);
+}
+
+pub fn reveal_ambushing_unit(trigger: On<Remove, InAmbush>, mut commands: Commands) {
+    commands
+        .entity(trigger.entity)
+        .try_insert(Visibility::Inherited);
+}
+
 pub fn set_unit_walking(
     trigger: On<Add, Moving>,
     is_unit: Query<Entity, With<Unit>>,
@@ -99,7 +111,9 @@ pub fn set_unit_after_play_once(
         }
 
         let new_animation = match animation {
-            UnitAnimation::Attack | UnitAnimation::Hit => UnitAnimation::Idle,
+            UnitAnimation::Attack | UnitAnimation::Hit | UnitAnimation::Block => {
+                UnitAnimation::Idle
+            }
             _ => *animation,
         };
 
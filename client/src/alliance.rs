@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use shared::server::players::team::AllianceNotice;
+
+/// Seconds an alliance notice stays on screen.
+const NOTICE_DURATION: f32 = 6.;
+
+pub struct AlliancePlugin;
+
+impl Plugin for AlliancePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(show_alliance_notice)
+            .add_systems(Update, hide_alliance_notice);
+    }
+}
+
+#[derive(Component, Deref, DerefMut)]
+struct AllianceBanner(Timer);
+
+fn show_alliance_notice(
+    trigger: On<AllianceNotice>,
+    banners: Query<Entity, With<AllianceBanner>>,
+    mut commands: Commands,
+) {
+    for entity in banners.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let text = match *trigger {
+        AllianceNotice::Proposed { by } => {
+            format!("The {by:?} king proposes an alliance, press L next to them to accept!")
+        }
+        AllianceNotice::Formed { with } => {
+            format!("You formed an alliance with the {with:?} king.")
+        }
+    };
+
+    commands.spawn((
+        AllianceBanner(Timer::from_seconds(NOTICE_DURATION, TimerMode::Once)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(55.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(
+            Text::new(text),
+            TextColor(Color::srgb(0.3, 0.8, 0.4)),
+            TextFont::from_font_size(30.)
+        )],
+    ));
+}
+
+fn hide_alliance_notice(
+    mut banners: Query<(Entity, &mut AllianceBanner)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut banner) in banners.iter_mut() {
+        if banner.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use bevy::prelude::*;
+
+use bevy::input::{
+    ButtonState, InputSystems,
+    keyboard::{Key, KeyboardInput},
+};
+use bevy_replicon::prelude::*;
+use console_protocol::{ConsoleLine, command_names, complete};
+use shared::{
+    Player,
+    server::console::in_game::{ConsoleOutput, ConsoleRequest},
+};
+
+const MAX_LOG_LINES: usize = 12;
+
+pub struct DevConsolePlugin;
+
+impl Plugin for DevConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DevConsole>()
+            .add_systems(PostStartup, setup_console)
+            .add_systems(
+                PreUpdate,
+                block_game_input
+                    .after(InputSystems)
+                    .run_if(|console: Res<DevConsole>| console.open),
+            )
+            .add_systems(
+                Update,
+                (console_input, receive_output, update_console)
+                    .chain()
+                    .before(ClientSystems::Send),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+struct DevConsole {
+    open: bool,
+    input: String,
+    log: Vec<String>,
+    history: Vec<String>,
+    history_index: Option<usize>,
+}
+
+impl DevConsole {
+    fn print(&mut self, text: impl AsRef<str>) {
+        self.log.extend(text.as_ref().lines().map(String::from));
+        let overflow = self.log.len().saturating_sub(MAX_LOG_LINES);
+        self.log.drain(..overflow);
+    }
+}
+
+#[derive(Component)]
+struct ConsoleRoot;
+
+#[derive(Component)]
+struct ConsoleLog;
+
+#[derive(Component)]
+struct ConsolePrompt;
+
+fn setup_console(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::FlexEnd,
+            width: Val::Percent(100.),
+            height: Val::Percent(40.),
+            top: Val::Px(0.),
+            left: Val::Px(0.),
+            padding: UiRect::all(Val::Px(8.)),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0., 0., 0., 0.75)),
+        GlobalZIndex(100),
+        Visibility::Hidden,
+        ConsoleRoot,
+        children![
+            (
+                Text::default(),
+                TextFont::from_font_size(16.),
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                ConsoleLog,
+            ),
+            (
+                Text::new("> "),
+                TextFont::from_font_size(16.),
+                TextColor(Color::WHITE),
+                ConsolePrompt,
+            )
+        ],
+    ));
+}
+
+/// Keeps the game from reacting to the keys typed into the console.
+fn block_game_input(mut keyboard_input: ResMut<ButtonInput<KeyCode>>) {
+    keyboard_input.reset_all();
+}
+
+fn console_input(
+    mut keyboard: MessageReader<KeyboardInput>,
+    mut console: ResMut<DevConsole>,
+    players: Query<&Player>,
+    mut commands: Commands,
+) {
+    for event in keyboard.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        if event.key_code == KeyCode::Backquote {
+            console.open = !console.open;
+            continue;
+        }
+
+        if !console.open {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Escape => console.open = false,
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                submit(line.trim(), &mut console, &mut commands);
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Tab => {
+                let mut names = command_names::<ConsoleLine>();
+                names.extend(["help", "clear"].map(String::from));
+
+                if let Some(completed) =
+                    complete(&console.input, &names, &player_selectors(&players))
+                {
+                    console.input = completed;
+                }
+            }
+            Key::ArrowUp => {
+                let index = match console.history_index {
+                    Some(index) => index.saturating_sub(1),
+                    None => console.history.len().saturating_sub(1),
+                };
+                if let Some(line) = console.history.get(index).cloned() {
+                    console.history_index = Some(index);
+                    console.input = line;
+                }
+            }
+            Key::ArrowDown => {
+                let Some(index) = console.history_index else {
+                    continue;
+                };
+                match console.history.get(index + 1).cloned() {
+                    Some(line) => {
+                        console.history_index = Some(index + 1);
+                        console.input = line;
+                    }
+                    None => {
+                        console.history_index = None;
+                        console.input.clear();
+                    }
+                }
+            }
+            _ => {
+                if let Some(text) = &event.text
+                    && text.chars().all(|c| !c.is_control())
+                {
+                    console.input.push_str(text);
+                }
+            }
+        }
+    }
+}
+
+fn submit(line: &str, console: &mut DevConsole, commands: &mut Commands) {
+    if line.is_empty() {
+        return;
+    }
+
+    console.history.push(line.to_string());
+    console.history_index = None;
+    console.print(format!("> {line}"));
+
+    if line == "clear" {
+        console.log.clear();
+        return;
+    }
+
+    let command = match ConsoleLine::parse_line(line) {
+        Ok(command) => command,
+        Err(error) => {
+            console.print(error.to_string());
+            return;
+        }
+    };
+
+    match command.params() {
+        Ok(params) => commands.client_trigger(ConsoleRequest {
+            method: command.method().into(),
+            params: params.map(|params| params.to_string()),
+        }),
+        Err(error) => console.print(format!("error: {error}")),
+    }
+}
+
+fn player_selectors(players: &Query<&Player>) -> Vec<String> {
+    let mut selectors = vec![String::from("local")];
+    for player in players.iter() {
+        selectors.push(player.id.to_string());
+        selectors.push(format!("{:?}", player.color).to_lowercase());
+    }
+    selectors
+}
+
+fn receive_output(mut output: MessageReader<ConsoleOutput>, mut console: ResMut<DevConsole>) {
+    for output in output.read() {
+        match output.error {
+            true => console.print(format!("error: {}", output.text)),
+            false => console.print(&output.text),
+        }
+    }
+}
+
+fn update_console(
+    console: Res<DevConsole>,
+    mut root: Query<&mut Visibility, With<ConsoleRoot>>,
+    mut log: Query<&mut Text, (With<ConsoleLog>, Without<ConsolePrompt>)>,
+    mut prompt: Query<&mut Text, (With<ConsolePrompt>, Without<ConsoleLog>)>,
+) -> Result {
+    if !console.is_changed() {
+        return Ok(());
+    }
+
+    *root.single_mut()? = match console.open {
+        true => Visibility::Visible,
+        false => Visibility::Hidden,
+    };
+    log.single_mut()?.0 = console.log.join("\n");
+    prompt.single_mut()?.0 = format!("> {}_", console.input);
+    Ok(())
+}
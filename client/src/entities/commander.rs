@@ -5,6 +5,7 @@ use animations::{
     ui::{
         animations::SpriteShaking,
         army_formations::{FormationIconSpriteSheet, FormationIcons},
+        army_orders::{OrderIconSpriteSheet, OrderIcons},
         commander_menu::{CommanderMenuNodes, CommanderMenuSpriteSheet},
     },
 };
@@ -14,12 +15,13 @@ use shared::{
     ControlledPlayer, PlayerState, Vec3LayerExt,
     map::Layers,
     networking::UnitType,
+    player_attacks::ArmyOrder,
     server::{
         buildings::recruiting::{Flag, FlagHolder},
         entities::commander::{
             ArmyFlagAssignments, ArmyFormation, ArmyPosition, CommanderAssignmentReject,
             CommanderAssignmentRequest, CommanderCampInteraction, CommanderInteraction,
-            CommanderPickFlag,
+            CommanderOrder, CommanderPickFlag,
         },
     },
 };
@@ -35,6 +37,7 @@ enum MainMenuEntries {
     Camp,
     Formation,
     Flag,
+    Orders,
 }
 
 #[derive(Event, Deref)]
@@ -57,7 +60,9 @@ impl Plugin for CommanderInteractionPlugin {
             .add_observer(highligh_formation)
             .add_observer(select_create_camp)
             .add_observer(open_slots_dialog)
+            .add_observer(open_orders_dialog)
             .add_observer(send_selected)
+            .add_observer(send_order)
             .add_observer(cleanup_menu_extras)
             .add_observer(draw_hovering_flag)
             .add_observer(assigment_warning)
@@ -70,6 +75,7 @@ impl Plugin for CommanderInteractionPlugin {
             .add_plugins((
                 MenuPlugin::<MainMenuEntries>::default(),
                 MenuPlugin::<ArmyPosition>::default(),
+                MenuPlugin::<ArmyOrder>::default(),
             ));
     }
 }
@@ -136,6 +142,19 @@ fn open_commander_dialog(
                     ..Default::default()
                 },
             ),
+            MenuNode::bundle(
+                MainMenuEntries::Orders,
+                Sprite {
+                    image: texture.clone(),
+                    texture_atlas: Some(
+                        menu_sprite_sheet
+                            .sprite_sheet
+                            .texture_atlas(CommanderMenuNodes::Orders),
+                    ),
+                    custom_size: Some(Vec2::splat(15.)),
+                    ..Default::default()
+                },
+            ),
         ])
         .with_gap(15.),
     ));
@@ -236,6 +255,62 @@ fn open_slots_dialog(
     Ok(())
 }
 
+fn open_orders_dialog(
+    trigger: On<SelectionEvent<MainMenuEntries>>,
+    transform: Query<&GlobalTransform>,
+    orders: Res<OrderIconSpriteSheet>,
+    mut commands: Commands,
+) -> Result {
+    let MainMenuEntries::Orders = trigger.selection else {
+        return Ok(());
+    };
+
+    let entry_position = transform.get(trigger.entry)?.translation();
+
+    let menu_nodes: Vec<MenuNode<ArmyOrder>> = [
+        ArmyOrder::FollowFlag,
+        ArmyOrder::HoldPosition,
+        ArmyOrder::DefendBuilding,
+        ArmyOrder::Charge,
+    ]
+    .into_iter()
+    .map(|order| {
+        let icon = match order {
+            ArmyOrder::FollowFlag => OrderIcons::FollowFlag,
+            ArmyOrder::HoldPosition => OrderIcons::HoldPosition,
+            ArmyOrder::DefendBuilding => OrderIcons::DefendBuilding,
+            ArmyOrder::Charge => OrderIcons::Charge,
+        };
+
+        MenuNode::bundle(
+            order,
+            Sprite {
+                image: orders.sprite_sheet.texture.clone(),
+                texture_atlas: Some(orders.sprite_sheet.texture_atlas(icon)),
+                ..Default::default()
+            },
+        )
+    })
+    .collect();
+
+    commands.spawn((
+        Visibility::default(),
+        entry_position
+            .offset_x(-5.5)
+            .offset_y(15.)
+            .with_layer(Layers::Item),
+        Menu::new(menu_nodes)
+            .with_gap(15.)
+            .with_entry_scale(1. / 5.),
+    ));
+    Ok(())
+}
+
+fn send_order(trigger: On<SelectionEvent<ArmyOrder>>, mut commands: Commands) {
+    commands.client_trigger(CommanderOrder(trigger.selection));
+    commands.trigger(CloseEvent);
+}
+
 fn send_selected(trigger: On<SelectionEvent<ArmyPosition>>, mut commands: Commands) {
     let SelectionEvent {
         selection: slot,
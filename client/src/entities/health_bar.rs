@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+
+use shared::{BoxCollider, server::entities::health::HealthFraction};
+
+const BAR_WIDTH: f32 = 16.;
+const BAR_HEIGHT: f32 = 2.;
+
+/// Space between the top of the collider and the bar.
+const BAR_MARGIN: f32 = 4.;
+
+pub struct HealthBarPlugin;
+
+impl Plugin for HealthBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_health_bars)
+            .add_observer(remove_health_bar);
+    }
+}
+
+/// The bar shown over a damaged unit or building.
+#[derive(Component)]
+struct HealthBar {
+    background: Entity,
+    fill: Entity,
+}
+
+fn fill_size(fraction: f32) -> (Vec2, Vec3) {
+    let width = BAR_WIDTH * fraction.clamp(0., 1.);
+    (
+        Vec2::new(width, BAR_HEIGHT),
+        Vec3::new((width - BAR_WIDTH) / 2., 0., 0.1),
+    )
+}
+
+/// Only damaged units and buildings show a bar.
+fn update_health_bars(
+    owners: Query<
+        (
+            Entity,
+            &HealthFraction,
+            Option<&BoxCollider>,
+            Option<&HealthBar>,
+        ),
+        Changed<HealthFraction>,
+    >,
+    mut fills: Query<(&mut Transform, &mut Sprite)>,
+    mut commands: Commands,
+) {
+    for (entity, fraction, collider, bar) in owners.iter() {
+        if **fraction >= 1. {
+            if let Some(bar) = bar {
+                commands.entity(bar.background).despawn();
+                commands.entity(entity).remove::<HealthBar>();
+            }
+            continue;
+        }
+
+        let (size, offset) = fill_size(**fraction);
+
+        if let Some(bar) = bar {
+            if let Ok((mut transform, mut sprite)) = fills.get_mut(bar.fill) {
+                transform.translation = offset;
+                sprite.custom_size = Some(size);
+            }
+            continue;
+        }
+
+        let height = collider
+            .map(|collider| collider.half_size().y + collider.offset.unwrap_or_default().y)
+            .unwrap_or(16.);
+
+        let background = commands
+            .spawn((
+                Sprite {
+                    color: Color::srgb(0.15, 0.15, 0.15),
+                    custom_size: Some(Vec2::new(BAR_WIDTH, BAR_HEIGHT)),
+                    ..default()
+                },
+                Transform::from_xyz(0., height + BAR_MARGIN, 1.),
+                ChildOf(entity),
+            ))
+            .id();
+        let fill = commands
+            .spawn((
+                Sprite {
+                    color: Color::srgb(0.8, 0.15, 0.15),
+                    custom_size: Some(size),
+                    ..default()
+                },
+                Transform::from_translation(offset),
+                ChildOf(background),
+            ))
+            .id();
+        commands
+            .entity(entity)
+            .insert(HealthBar { background, fill });
+    }
+}
+
+fn remove_health_bar(
+    trigger: On<Remove, HealthFraction>,
+    bars: Query<&HealthBar>,
+    mut commands: Commands,
+) {
+    if let Ok(bar) = bars.get(trigger.entity) {
+        commands.entity(bar.background).try_despawn();
+        commands.entity(trigger.entity).try_remove::<HealthBar>();
+    }
+}
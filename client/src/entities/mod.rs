@@ -1,13 +1,19 @@
 use bevy::prelude::*;
 
 use commander::CommanderInteractionPlugin;
+use health_bar::HealthBarPlugin;
 use highlight::HighlightPlugin;
 use item_assignment::ItemAssignmentPlugin;
 use items::ItemsPlugin;
+use morale::MoralePlugin;
 use spawn::SpawnPlugin;
+use status::StatusPlugin;
 
+mod health_bar;
 mod item_assignment;
+mod morale;
 mod spawn;
+mod status;
 
 pub mod commander;
 pub mod items;
@@ -20,6 +26,9 @@ impl Plugin for EntitiesPlugin {
             .add_plugins(HighlightPlugin)
             .add_plugins(ItemsPlugin)
             .add_plugins(ItemAssignmentPlugin)
-            .add_plugins(CommanderInteractionPlugin);
+            .add_plugins(CommanderInteractionPlugin)
+            .add_plugins(MoralePlugin)
+            .add_plugins(StatusPlugin)
+            .add_plugins(HealthBarPlugin);
     }
 }
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+use shared::server::{ai::morale::MoraleState, entities::status::ActiveStatuses};
+
+pub struct MoralePlugin;
+
+impl Plugin for MoralePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, tint_wavering_units);
+    }
+}
+
+/// Yellow for wavering units, pale blue for routing ones.
+pub(super) fn morale_tint(state: &MoraleState) -> Color {
+    match state {
+        MoraleState::Steady => Color::WHITE,
+        MoraleState::Wavering => Color::srgb(1., 0.9, 0.6),
+        MoraleState::Routing => Color::srgb(0.7, 0.75, 1.),
+    }
+}
+
+/// Status effect tints take precedence while they last.
+fn tint_wavering_units(
+    mut query: Query<(&mut Sprite, &MoraleState, Option<&ActiveStatuses>), Changed<MoraleState>>,
+) {
+    for (mut sprite, state, statuses) in query.iter_mut() {
+        if statuses.is_some_and(|statuses| !statuses.is_empty()) {
+            continue;
+        }
+        sprite.color = morale_tint(state);
+    }
+}
@@ -23,10 +23,10 @@ use shared::{
     map::buildings::{Building, RecruitBuilding},
     player_port::Portal,
     server::{
-        buildings::{recruiting::Flag, siege_camp::SiegeCamp},
+        buildings::{recruiting::Flag, siege_camp::SiegeCamp, siege_engine::SiegeEngine},
         entities::{Unit, UnitAnimation, health::Health},
         physics::projectile::ProjectileType,
-        players::{chest::Chest, mount::Mount},
+        players::{chest::Chest, interaction::Interactable, loot::GoldPile, mount::Mount},
     },
 };
 use sprite_variant_loader::loader::{SpriteVariants, SpriteVariantsAssetsExt};
@@ -39,6 +39,8 @@ impl Plugin for SpawnPlugin {
         app.add_observer(init_player_sprite)
             .add_observer(init_recruit_building_sprite)
             .add_observer(init_camp_sprite)
+            .add_observer(init_siege_engine_sprite)
+            .add_observer(show_built_siege_engine)
             .add_observer(init_unit_sprite)
             .add_observer(init_flag_sprite)
             .add_observer(init_scene_end_sprite)
@@ -47,6 +49,7 @@ impl Plugin for SpawnPlugin {
             .add_observer(init_horse_sprite)
             .add_observer(init_projectile_sprite)
             .add_observer(init_chest_sprite)
+            .add_observer(init_gold_sprite)
             .add_observer(init_local_player);
     }
 }
@@ -122,6 +125,35 @@ fn init_camp_sprite(
     Ok(())
 }
 
+/// Siege engines nobody paid for yet are shown see-through.
+const BLUEPRINT_ALPHA: f32 = 0.5;
+
+fn init_siege_engine_sprite(
+    trigger: On<Add, SiegeEngine>,
+    mut engines: Query<(&mut Sprite, &SiegeEngine, Has<Interactable>)>,
+    asset_server: Res<AssetServer>,
+) -> Result {
+    let (mut sprite, engine, is_blueprint) = engines.get_mut(trigger.entity)?;
+    let texture = match engine {
+        SiegeEngine::Catapult => "sprites/buildings/catapult.png",
+        SiegeEngine::Ram => "sprites/buildings/ram.png",
+    };
+    sprite.image = asset_server.load::<Image>(texture);
+    if is_blueprint {
+        sprite.color = Color::WHITE.with_alpha(BLUEPRINT_ALPHA);
+    }
+    Ok(())
+}
+
+fn show_built_siege_engine(
+    trigger: On<Remove, Interactable>,
+    mut engines: Query<&mut Sprite, With<SiegeEngine>>,
+) {
+    if let Ok(mut sprite) = engines.get_mut(trigger.entity) {
+        sprite.color = Color::WHITE;
+    }
+}
+
 fn init_unit_sprite(
     trigger: On<Add, Unit>,
     mut units: Query<(&mut Sprite, &Unit, Option<&Health>)>,
@@ -348,9 +380,13 @@ fn init_projectile_sprite(
 ) -> Result {
     let (mut sprite, projectile_type) = projectile.get_mut(trigger.entity)?;
 
-    let texture = match projectile_type {
-        ProjectileType::Arrow => projectiles.sprite_sheet.texture_atlas(Projectiles::Arrow),
+    let part = match projectile_type {
+        ProjectileType::Arrow => Projectiles::Arrow,
+        ProjectileType::FireArrow => Projectiles::FireArrow,
+        ProjectileType::Bomb => Projectiles::Bomb,
+        ProjectileType::Boulder => Projectiles::Boulder,
     };
+    let texture = projectiles.sprite_sheet.texture_atlas(part);
     sprite.texture_atlas = Some(texture);
     sprite.image = projectiles.sprite_sheet.texture.clone();
     Ok(())
@@ -373,3 +409,13 @@ fn init_chest_sprite(
     });
     Ok(())
 }
+
+fn init_gold_sprite(
+    trigger: On<Add, GoldPile>,
+    mut piles: Query<&mut Sprite>,
+    asset_server: Res<AssetServer>,
+) -> Result {
+    let mut sprite = piles.get_mut(trigger.entity)?;
+    sprite.image = asset_server.load::<Image>("sprites/objects/gold.png");
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use shared::server::{
+    ai::morale::MoraleState,
+    entities::status::{ActiveStatuses, StatusKind},
+};
+
+use super::morale::morale_tint;
+
+/// Height of the status icons above a unit's feet.
+const ICON_HEIGHT: f32 = 24.;
+const ICON_SIZE: f32 = 3.;
+const ICON_SPACING: f32 = 4.;
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (tint_affected_units, show_status_icons));
+    }
+}
+
+#[derive(Component)]
+struct StatusIcon;
+
+fn status_color(kind: StatusKind) -> Color {
+    match kind {
+        StatusKind::Burning => Color::srgb(1., 0.55, 0.2),
+        StatusKind::Slowed => Color::srgb(0.55, 0.85, 1.),
+        StatusKind::Stunned => Color::srgb(1., 1., 0.4),
+        StatusKind::Bleeding => Color::srgb(0.85, 0.15, 0.15),
+    }
+}
+
+/// Tinted by the first active effect, by morale once the effects wear off.
+fn tint_affected_units(
+    mut query: Query<(&mut Sprite, &ActiveStatuses, Option<&MoraleState>), Changed<ActiveStatuses>>,
+) {
+    for (mut sprite, statuses, morale) in query.iter_mut() {
+        sprite.color = match statuses.first() {
+            Some(kind) => status_color(*kind).mix(&Color::WHITE, 0.4),
+            None => morale.map(morale_tint).unwrap_or(Color::WHITE),
+        };
+    }
+}
+
+/// A row of small colored squares above the unit, one per active effect.
+fn show_status_icons(
+    units: Query<(Entity, &ActiveStatuses, Option<&Children>), Changed<ActiveStatuses>>,
+    icons: Query<(), With<StatusIcon>>,
+    mut commands: Commands,
+) {
+    for (entity, statuses, children) in units.iter() {
+        for child in children.into_iter().flatten() {
+            if icons.contains(*child) {
+                commands.entity(*child).despawn();
+            }
+        }
+
+        let start = -(statuses.len().saturating_sub(1) as f32) * ICON_SPACING / 2.;
+        for (i, kind) in statuses.iter().enumerate() {
+            commands.spawn((
+                StatusIcon,
+                Sprite {
+                    color: status_color(*kind),
+                    custom_size: Some(Vec2::splat(ICON_SIZE)),
+                    ..default()
+                },
+                Transform::from_xyz(start + i as f32 * ICON_SPACING, ICON_HEIGHT, 1.),
+                ChildOf(entity),
+            ));
+        }
+    }
+}
@@ -1,23 +1,37 @@
 use bevy::{
-    color::palettes::css::{BLUE, GREEN, RED, YELLOW},
+    color::palettes::css::{BLUE, GREEN, ORANGE, RED, YELLOW},
     prelude::*,
 };
 
 use shared::{
-    BoxCollider,
-    server::entities::{MeleeRange, ProjectileRange, Sight},
+    BoxCollider, Vec3LayerExt,
+    map::Layers,
+    server::{
+        ai::debug::AIDebugState,
+        entities::{MeleeRange, ProjectileRange, Sight},
+    },
 };
 
+/// Height above a unit's feet its AI state is shown at.
+const AI_LABEL_HEIGHT: f32 = 40.;
+
 pub struct GizmosPlugin;
 
 impl Plugin for GizmosPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(GizmosSettings::default());
 
-        app.add_systems(Update, (draw_range, draw_collider));
+        app.add_observer(spawn_ai_label).add_systems(
+            Update,
+            (draw_range, draw_collider, draw_ai_target, update_ai_label),
+        );
     }
 }
 
+/// Floating text showing the [`AIDebugState`] of a unit.
+#[derive(Component)]
+struct AIDebugLabel(Entity);
+
 #[derive(Resource, Default)]
 pub struct GizmosSettings {
     pub on: bool,
@@ -72,3 +86,81 @@ fn draw_collider(
         );
     }
 }
+
+fn draw_ai_target(
+    mut gizmos: Gizmos,
+    settings: Res<GizmosSettings>,
+    query: Query<(&GlobalTransform, &AIDebugState)>,
+    targets: Query<&GlobalTransform>,
+) {
+    if !settings.on {
+        return;
+    }
+    for (transform, state) in query.iter() {
+        let Some(target) = state.target.and_then(|target| targets.get(target).ok()) else {
+            continue;
+        };
+        let height = Vec2::new(0., AI_LABEL_HEIGHT / 2.);
+        gizmos.line_2d(
+            transform.translation().truncate() + height,
+            target.translation().truncate() + height,
+            ORANGE,
+        );
+    }
+}
+
+fn spawn_ai_label(trigger: On<Add, AIDebugState>, mut commands: Commands) {
+    commands.spawn((
+        AIDebugLabel(trigger.entity),
+        Text2d::new(""),
+        TextFont {
+            font_size: 40.0,
+            ..default()
+        },
+        TextLayout::new_with_justify(Justify::Center).with_no_wrap(),
+        Transform::from_scale(Vec3::new(0.2, 0.2, 1.)),
+        Visibility::Hidden,
+    ));
+}
+
+/// Labels are not children of their unit, units are flipped by scaling them on x.
+fn update_ai_label(
+    mut labels: Query<(
+        Entity,
+        &AIDebugLabel,
+        &mut Text2d,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    units: Query<(&GlobalTransform, &AIDebugState)>,
+    settings: Res<GizmosSettings>,
+    mut commands: Commands,
+) {
+    for (entity, label, mut text, mut transform, mut visibility) in labels.iter_mut() {
+        let Ok((unit_transform, state)) = units.get(label.0) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        if !settings.on {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let content = format!(
+            "{}\n{}",
+            state.node.as_deref().unwrap_or("-"),
+            state.behaviour
+        );
+        if text.0 != content {
+            text.0 = content;
+        }
+
+        transform.translation = unit_transform
+            .translation()
+            .offset_y(AI_LABEL_HEIGHT)
+            .with_layer(Layers::UI)
+            .translation;
+    }
+}
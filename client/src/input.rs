@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 
 use bevy_replicon::client::ClientSystems;
-use shared::networking::LobbyMessage;
+use shared::networking::{Difficulty, LobbyMessage};
 
 use crate::gizmos::GizmosSettings;
 
@@ -24,6 +24,21 @@ fn lobby_input(
     if keyboard_input.just_pressed(KeyCode::Enter) {
         lobby_events.write(LobbyMessage::StartGame);
     }
+    if keyboard_input.just_pressed(KeyCode::Digit1) {
+        lobby_events.write(LobbyMessage::AddRival(Difficulty::Easy));
+    }
+    if keyboard_input.just_pressed(KeyCode::Digit2) {
+        lobby_events.write(LobbyMessage::AddRival(Difficulty::Normal));
+    }
+    if keyboard_input.just_pressed(KeyCode::Digit3) {
+        lobby_events.write(LobbyMessage::AddRival(Difficulty::Hard));
+    }
+    if keyboard_input.just_pressed(KeyCode::Backspace) {
+        lobby_events.write(LobbyMessage::RemoveRival);
+    }
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        lobby_events.write(LobbyMessage::CycleTeam);
+    }
 }
 
 fn gizmos_settings(
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+use game_world::rival::RivalKing;
+use shared::{ControlledPlayer, GameState, Player, server::players::team::Team};
+
+pub struct LobbyPlugin;
+
+impl Plugin for LobbyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::MainMenu), spawn_lobby_panel)
+            .add_systems(
+                Update,
+                update_lobby_panel.run_if(in_state(GameState::MainMenu)),
+            )
+            .add_systems(OnExit(GameState::MainMenu), despawn_lobby_panel);
+    }
+}
+
+/// Lists the players waiting in the lobby.
+#[derive(Component)]
+struct LobbyPanel;
+
+fn spawn_lobby_panel(mut commands: Commands) {
+    commands.spawn((
+        LobbyPanel,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(15.0),
+            left: Val::Px(15.0),
+            ..default()
+        },
+        Text::new(""),
+        TextColor(Color::WHITE),
+        TextFont::from_font_size(20.),
+    ));
+}
+
+fn update_lobby_panel(
+    mut panel: Query<&mut Text, With<LobbyPanel>>,
+    players: Query<(
+        &Player,
+        Option<&Team>,
+        Option<&RivalKing>,
+        Has<ControlledPlayer>,
+    )>,
+) -> Result {
+    let mut text = panel.single_mut()?;
+
+    let mut players: Vec<_> = players.iter().collect();
+    players.sort_by_key(|(player, ..)| player.id);
+
+    let mut lines = vec![String::from("Players")];
+    for (player, team, rival, controlled) in &players {
+        let mut line = format!("{:?}", player.color);
+        match team {
+            Some(Team(team)) => line += &format!(", team {team}"),
+            None => line += ", alone",
+        }
+        if let Some(rival) = rival {
+            line += &format!(", rival ({:?})", rival.difficulty);
+        }
+        if *controlled {
+            line += " (you)";
+        }
+        lines.push(line);
+    }
+
+    // The host has id 0 and is the only one who can add rivals.
+    let host = players
+        .iter()
+        .any(|(player, _, _, controlled)| *controlled && player.id == 0);
+    lines.push(String::new());
+    lines.push(String::from("Tab: change team"));
+    if host {
+        lines.push(String::from(
+            "1/2/3: add easy/normal/hard rival, Backspace: remove rival, Enter: start",
+        ));
+    } else {
+        lines.push(String::from("Waiting for the host to start"));
+    }
+
+    let lines = lines.join("\n");
+    if text.0 != lines {
+        text.0 = lines;
+    }
+    Ok(())
+}
+
+fn despawn_lobby_panel(panel: Query<Entity, With<LobbyPanel>>, mut commands: Commands) {
+    for entity in panel.iter() {
+        commands.entity(entity).despawn();
+    }
+}
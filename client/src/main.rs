@@ -2,6 +2,7 @@ use bevy::prelude::*;
 
 use bevy::audio::{AudioPlugin, SpatialScale, Volume};
 use bevy_parallax::ParallaxPlugin;
+use console::DevConsolePlugin;
 use game_world::GameWorldPlugin;
 use gizmos::GizmosPlugin;
 use networking::join_server::JoinServerPlugin;
@@ -20,16 +21,23 @@ use input::InputPlugin;
 use travel::TravelPlugin;
 
 use crate::{
-    background::BackgroundPlugin, background_sound::BackgroundSoundPlugin, defeat::DefeatPlugin,
+    alliance::AlliancePlugin, background::BackgroundPlugin,
+    background_sound::BackgroundSoundPlugin, defeat::DefeatPlugin, lobby::LobbyPlugin,
+    match_summary::MatchSummaryPlugin, raid_warning::RaidWarningPlugin,
 };
 
+pub mod alliance;
 pub mod background;
 pub mod camera;
+pub mod console;
 pub mod defeat;
 pub mod entities;
 pub mod gizmos;
 pub mod input;
+pub mod lobby;
+pub mod match_summary;
 pub mod networking;
+pub mod raid_warning;
 pub mod ui;
 pub mod widgets;
 
@@ -99,9 +107,16 @@ fn main() {
             UiPlugin,
             BackgroundSoundPlugin,
             GizmosPlugin,
-            DefeatPlugin,
             GameWorldPlugin,
             TravelPlugin,
+            DevConsolePlugin,
+        ))
+        .add_plugins((
+            LobbyPlugin,
+            DefeatPlugin,
+            RaidWarningPlugin,
+            AlliancePlugin,
+            MatchSummaryPlugin,
         ));
 
     client.add_systems(OnEnter(GameState::MainMenu), setup_background);
@@ -109,6 +124,27 @@ fn main() {
     if args.contains(&String::from("server")) {
         client.add_plugins(ServerNetworkPlugin);
 
+        if args.contains(&String::from("cheats")) {
+            use shared::server::console::in_game::CheatsEnabled;
+
+            client.insert_resource(CheatsEnabled);
+        }
+
+        if args.contains(&String::from("debug-ai")) {
+            use shared::server::ai::debug::AIDebugEnabled;
+
+            client.insert_resource(AIDebugEnabled);
+        }
+
+        if args.contains(&String::from("friendly-fire")) {
+            use shared::server::physics::projectile::ProjectileSettings;
+
+            client.insert_resource(ProjectileSettings {
+                friendly_fire: true,
+                ..default()
+            });
+        }
+
         #[cfg(feature = "steam")]
         {
             use aeronet_steam::server::SteamNetServerPlugin;
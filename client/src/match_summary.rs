@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use shared::server::stats::{MatchSummary, PlayerStats};
+
+const HEADER: [&str; 9] = [
+    "Player",
+    "Dealt",
+    "Taken",
+    "Kills",
+    "Losses",
+    "Buildings",
+    "Gold",
+    "Spent",
+    "Chests",
+];
+
+pub struct MatchSummaryPlugin;
+
+impl Plugin for MatchSummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(show_match_summary);
+    }
+}
+
+fn show_match_summary(trigger: On<MatchSummary>, mut commands: Commands) {
+    let MatchSummary { elapsed, players } = trigger.event();
+
+    let minutes = (elapsed / 60.) as u32;
+    let seconds = (elapsed % 60.) as u32;
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(380.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            children![(
+                Text::new(format!("Match over after {minutes}:{seconds:02}")),
+                TextColor(Color::WHITE),
+                TextFont::from_font_size(30.)
+            )],
+        ))
+        .with_children(|summary| {
+            summary.spawn(row(HEADER.map(String::from)));
+            for player in players {
+                summary.spawn(row(player_row(player)));
+            }
+        });
+}
+
+fn player_row(player: &PlayerStats) -> [String; 9] {
+    let dealt: f32 = player.damage_dealt.values().sum();
+    let taken: f32 = player.damage_taken.values().sum();
+    let spent =
+        player.gold_spent_recruitment + player.gold_spent_respawn + player.gold_spent_buildings;
+
+    [
+        player.color.clone(),
+        format!("{dealt:.0}"),
+        format!("{taken:.0}"),
+        player.kills.to_string(),
+        player.losses.to_string(),
+        player.buildings_destroyed.to_string(),
+        player.gold_earned.to_string(),
+        spent.to_string(),
+        player.chests_opened.to_string(),
+    ]
+}
+
+fn row(cells: [String; 9]) -> impl Bundle {
+    (
+        Node {
+            column_gap: Val::Px(12.0),
+            ..default()
+        },
+        Children::spawn(SpawnIter(cells.into_iter().map(|cell| {
+            (
+                Node {
+                    width: Val::Px(90.0),
+                    ..default()
+                },
+                children![(
+                    Text::new(cell),
+                    TextColor(Color::WHITE),
+                    TextFont::from_font_size(20.)
+                )],
+            )
+        }))),
+    )
+}
@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use game_world::raid::RaidWarning;
+
+/// Seconds a raid warning stays on screen.
+const WARNING_DURATION: f32 = 8.;
+
+pub struct RaidWarningPlugin;
+
+impl Plugin for RaidWarningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(show_raid_warning)
+            .add_systems(Update, hide_raid_warning);
+    }
+}
+
+#[derive(Component, Deref, DerefMut)]
+struct RaidWarningBanner(Timer);
+
+fn show_raid_warning(trigger: On<RaidWarning>, mut commands: Commands) {
+    let RaidWarning {
+        raiders,
+        departs_in,
+    } = *trigger;
+
+    commands.spawn((
+        RaidWarningBanner(Timer::from_seconds(WARNING_DURATION, TimerMode::Once)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(15.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(
+            Text::new(format!(
+                "{raiders} bandits gather to raid your base, they set off in {departs_in:.0}s!"
+            )),
+            TextColor(Color::srgb(0.9, 0.2, 0.2)),
+            TextFont::from_font_size(30.)
+        )],
+    ));
+}
+
+fn hide_raid_warning(
+    mut banners: Query<(Entity, &mut RaidWarningBanner)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut banner) in banners.iter_mut() {
+        if banner.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use clap::{CommandFactory, Parser, Subcommand, ValueHint};
+use serde::Serialize;
+use serde_json::{Value, to_value};
+
+use crate::*;
+
+pub const UNITS: [&str; 3] = ["archer", "pikemen", "shield"];
+
+/// Commands that are sent to the game as a single BRP request.
+#[derive(Subcommand, Clone)]
+pub enum ConsoleCommand {
+    /// List all players and the selectors to address them.
+    Players,
+    /// Show the combat and economy statistics of the current match.
+    Stats,
+    /// Show the loot drop tables, or change them for the running game.
+    Loot {
+        /// Bandit drops as `gold_chance,gold_min,gold_max,item_chance`.
+        #[arg(long)]
+        bandit: Option<BrpDropTable>,
+
+        /// Drops of destroyed buildings as `gold_chance,gold_min,gold_max,item_chance`.
+        #[arg(long)]
+        building: Option<BrpDropTable>,
+
+        /// Share of their gold knocked out kings drop, from 0 to 1.
+        #[arg(long)]
+        king_gold_share: Option<f32>,
+    },
+    #[command(visible_alias = "items")]
+    RandomItems {
+        #[arg(short, long, value_hint = ValueHint::CommandWithArguments, default_value_t = PlayerSelector::Local)]
+        player: PlayerSelector,
+    },
+    #[command(visible_alias = "unit")]
+    SpawnUnit {
+        #[arg(short, long, value_hint = ValueHint::CommandWithArguments)]
+        unit: Option<String>,
+
+        #[arg(short, long, value_hint = ValueHint::CommandWithArguments, default_value_t = PlayerSelector::Local)]
+        player: PlayerSelector,
+    },
+    #[command(visible_alias = "commander")]
+    SpawnFullCommander {
+        #[arg(short, long, value_hint = ValueHint::CommandWithArguments, default_value_t = PlayerSelector::Local)]
+        player: PlayerSelector,
+    },
+    #[command(visible_alias = "bandits")]
+    SpawnUnitAndBandits {
+        #[arg(short, long, value_hint = ValueHint::CommandWithArguments, default_value_t = PlayerSelector::Local)]
+        player: PlayerSelector,
+    },
+}
+
+impl ConsoleCommand {
+    pub fn method(&self) -> &'static str {
+        match self {
+            ConsoleCommand::Players => BRP_LIST_PLAYERS,
+            ConsoleCommand::Stats => BRP_MATCH_STATS,
+            ConsoleCommand::Loot { .. } => BRP_LOOT_TABLES,
+            ConsoleCommand::RandomItems { .. } => BRP_SPAWN_RANDOM_ITEM,
+            ConsoleCommand::SpawnUnit { .. } => BRP_SPAWN_UNIT,
+            ConsoleCommand::SpawnFullCommander { .. } => BRP_SPAWN_FULL_COMMANDER,
+            ConsoleCommand::SpawnUnitAndBandits { .. } => BRP_SPAWN_UNIT_AND_BANDITS,
+        }
+    }
+
+    pub fn params(&self) -> Result<Option<Value>, String> {
+        let params = match self.clone() {
+            ConsoleCommand::Players | ConsoleCommand::Stats => return Ok(None),
+            ConsoleCommand::Loot {
+                bandit,
+                building,
+                king_gold_share,
+            } => json_params(BrpLootTables {
+                bandit,
+                building,
+                king_gold_share,
+            }),
+            ConsoleCommand::RandomItems { player } => json_params(BrpSpawnItems { player }),
+            ConsoleCommand::SpawnUnit { unit, player } => {
+                let unit = unit.ok_or_else(|| {
+                    format!("missing --unit, expected one of {}", UNITS.join(", "))
+                })?;
+                json_params(BrpSpawnUnit { player, unit })
+            }
+            ConsoleCommand::SpawnFullCommander { player } => {
+                json_params(BrpSpawnFullCommander { player })
+            }
+            ConsoleCommand::SpawnUnitAndBandits { player } => {
+                json_params(BrpSpawnUnitAndBandits { player })
+            }
+        };
+        Ok(Some(params))
+    }
+}
+
+fn json_params(params: impl Serialize) -> Value {
+    to_value(params).expect("Unable to convert query parameters to a valid JSON value")
+}
+
+/// A single console command as typed into the in-game console.
+#[derive(Parser)]
+#[command(no_binary_name = true, disable_version_flag = true)]
+pub struct ConsoleLine {
+    #[command(subcommand)]
+    pub command: ConsoleCommand,
+}
+
+impl ConsoleLine {
+    pub fn parse_line(line: &str) -> Result<ConsoleCommand, clap::Error> {
+        Self::try_parse_from(line.split_whitespace()).map(|line| line.command)
+    }
+}
+
+/// Names and visible aliases of all subcommands of `P`, used for completion.
+pub fn command_names<P: CommandFactory>() -> Vec<String> {
+    P::command()
+        .get_subcommands()
+        .flat_map(|command| {
+            std::iter::once(command.get_name()).chain(command.get_visible_aliases())
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// Completes the last word of `input` with a command, player selector or unit name.
+///
+/// Returns the whole completed input, or `None` if nothing matches.
+pub fn complete(input: &str, commands: &[String], players: &[String]) -> Option<String> {
+    let (head, word) = match input.rfind(char::is_whitespace) {
+        Some(index) => input.split_at(index + 1),
+        None => ("", input),
+    };
+
+    let statement = head.rsplit(';').next().unwrap_or_default();
+    let candidates: Vec<&str> = match statement.split_whitespace().last() {
+        None => commands.iter().map(String::as_str).collect(),
+        Some("-p" | "--player") => players.iter().map(String::as_str).collect(),
+        Some("-u" | "--unit") => UNITS.to_vec(),
+        Some(_) if word.starts_with('-') => vec!["--player", "--unit"],
+        Some(_) => return None,
+    };
+
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.starts_with(word) && *candidate != word)
+        .map(|candidate| format!("{head}{candidate}"))
+}
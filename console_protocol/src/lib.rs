@@ -1,10 +1,99 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{convert::Infallible, fmt, str::FromStr};
+
+#[cfg(feature = "cli")]
+mod cli;
+
+#[cfg(feature = "cli")]
+pub use cli::*;
+
+/// Error codes returned by the game specific BRP methods, next to the ones defined by
+/// `bevy_remote`.
+pub mod error_codes {
+    /// No player matches the given selector.
+    pub const PLAYER_NOT_FOUND: i16 = -23601;
+
+    /// More than one player matches the given selector.
+    pub const PLAYER_AMBIGUOUS: i16 = -23602;
+
+    /// The host does not accept console commands from the in-game console.
+    pub const CHEATS_DISABLED: i16 = -23603;
+}
+
+/// Addresses a player in console commands.
+///
+/// Parsed from `local`, a numeric `Player.id` or a `PlayerColor` name such as `red`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerSelector {
+    /// The player hosting the server.
+    #[default]
+    Local,
+    Id(u64),
+    Color(String),
+}
+
+impl FromStr for PlayerSelector {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let selector = match s {
+            "local" | "server" | "host" => PlayerSelector::Local,
+            other => match other.parse::<u64>() {
+                Ok(id) => PlayerSelector::Id(id),
+                Err(_) => PlayerSelector::Color(other.to_lowercase()),
+            },
+        };
+        Ok(selector)
+    }
+}
+
+impl fmt::Display for PlayerSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerSelector::Local => write!(f, "local"),
+            PlayerSelector::Id(id) => write!(f, "{id}"),
+            PlayerSelector::Color(color) => write!(f, "{color}"),
+        }
+    }
+}
+
+pub const BRP_LIST_PLAYERS: &str = "player/list";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BrpPlayerInfo {
+    pub id: u64,
+    pub color: String,
+    pub local: bool,
+    pub connected: bool,
+}
+
+/// Renders the result of a console method for humans.
+pub fn format_result(method: &str, result: &Value) -> String {
+    let players = serde_json::from_value::<Vec<BrpPlayerInfo>>(result.clone());
+    let (BRP_LIST_PLAYERS, Ok(players)) = (method, players) else {
+        return format!("{result:#}");
+    };
+
+    let mut table = format!(
+        "{:<22} {:<10} {:<7} {:<9}",
+        "ID", "COLOR", "LOCAL", "CONNECTED"
+    );
+    for player in players {
+        table.push_str(&format!(
+            "\n{:<22} {:<10} {:<7} {:<9}",
+            player.id, player.color, player.local, player.connected
+        ));
+    }
+    table
+}
 
 pub const BRP_SPAWN_UNIT: &str = "player/spawn_unit";
 
 #[derive(Serialize, Deserialize)]
 pub struct BrpSpawnUnit {
-    pub player: u8,
+    pub player: PlayerSelector,
     pub unit: String,
 }
 
@@ -12,19 +101,149 @@ pub const BRP_SPAWN_RANDOM_ITEM: &str = "player/spawn_random_items";
 
 #[derive(Serialize, Deserialize)]
 pub struct BrpSpawnItems {
-    pub player: u8,
+    pub player: PlayerSelector,
 }
 
 pub const BRP_SPAWN_FULL_COMMANDER: &str = "player/spawn_full_commander";
 
 #[derive(Serialize, Deserialize)]
 pub struct BrpSpawnFullCommander {
-    pub player: u8,
+    pub player: PlayerSelector,
 }
 
 pub const BRP_SPAWN_UNIT_AND_BANDITS: &str = "player/spawn_ai_scenario";
 
 #[derive(Serialize, Deserialize)]
 pub struct BrpSpawnUnitAndBandits {
-    pub player: u8,
+    pub player: PlayerSelector,
+}
+
+/// Combat and economy statistics of every player in the current match.
+pub const BRP_MATCH_STATS: &str = "game/stats";
+
+/// Shows the loot drop tables of the running game, after changing the given ones.
+pub const BRP_LOOT_TABLES: &str = "game/loot";
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct BrpLootTables {
+    #[serde(default)]
+    pub bandit: Option<BrpDropTable>,
+    /// Dropped by buildings destroyed by an enemy.
+    #[serde(default)]
+    pub building: Option<BrpDropTable>,
+    /// Share of their gold knocked out kings drop, from 0 to 1.
+    #[serde(default)]
+    pub king_gold_share: Option<f32>,
+}
+
+/// Parsed from `gold_chance,gold_min,gold_max,item_chance`, such as `0.5,5,20,0.1`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BrpDropTable {
+    pub gold_chance: f32,
+    pub gold_min: u16,
+    pub gold_max: u16,
+    pub item_chance: f32,
+}
+
+impl FromStr for BrpDropTable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected gold_chance,gold_min,gold_max,item_chance, got `{s}`");
+
+        let [gold_chance, gold_min, gold_max, item_chance] = s.split(',').collect::<Vec<_>>()[..]
+        else {
+            return Err(invalid());
+        };
+        Ok(BrpDropTable {
+            gold_chance: gold_chance.trim().parse().map_err(|_| invalid())?,
+            gold_min: gold_min.trim().parse().map_err(|_| invalid())?,
+            gold_max: gold_max.trim().parse().map_err(|_| invalid())?,
+            item_chance: item_chance.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Streams gameplay events as they happen. Every message holds the events of one frame.
+pub const BRP_WATCH_EVENTS: &str = "game/events+watch";
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct BrpWatchEvents {
+    /// Only events involving this player.
+    #[serde(default)]
+    pub player: Option<PlayerSelector>,
+    /// Only events inside this game scene.
+    #[serde(default)]
+    pub scene: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BrpGameEvent {
+    /// Game scene the event happened in, if it happened in one.
+    pub scene: Option<usize>,
+    /// Ids of the players involved, used to filter the stream.
+    pub players: Vec<u64>,
+    #[serde(flatten)]
+    pub kind: BrpGameEventKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BrpGameEventKind {
+    Damage {
+        target: String,
+        damage: f32,
+        by: String,
+    },
+    UnitDeath {
+        unit: String,
+    },
+    BuildingChangeStart {
+        building: String,
+    },
+    BuildingChangeEnd {
+        building: String,
+    },
+    Recruit {
+        unit: String,
+    },
+    TravelStart {
+        from: usize,
+        to: usize,
+    },
+    TravelEnd {
+        scene: usize,
+    },
+    PlayerDefeated,
+    FlagPicked {
+        flag: String,
+    },
+    FlagDropped {
+        flag: String,
+    },
+}
+
+impl fmt::Display for BrpGameEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BrpGameEventKind::Damage { target, damage, by } => {
+                write!(f, "{target} took {damage} damage by {by}")
+            }
+            BrpGameEventKind::UnitDeath { unit } => write!(f, "{unit} died"),
+            BrpGameEventKind::BuildingChangeStart { building } => {
+                write!(f, "construction of {building} started")
+            }
+            BrpGameEventKind::BuildingChangeEnd { building } => {
+                write!(f, "construction of {building} finished")
+            }
+            BrpGameEventKind::Recruit { unit } => write!(f, "recruited {unit}"),
+            BrpGameEventKind::TravelStart { from, to } => {
+                write!(f, "started traveling from scene {from} to {to}")
+            }
+            BrpGameEventKind::TravelEnd { scene } => write!(f, "arrived in scene {scene}"),
+            BrpGameEventKind::PlayerDefeated => write!(f, "was defeated"),
+            BrpGameEventKind::FlagPicked { flag } => write!(f, "picked up {flag}"),
+            BrpGameEventKind::FlagDropped { flag } => write!(f, "dropped {flag}"),
+        }
+    }
 }
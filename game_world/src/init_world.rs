@@ -114,6 +114,22 @@ fn init_world(
     Ok(())
 }
 
+pub(crate) fn bandit(behaviour: BanditBehaviour) -> impl Bundle {
+    (
+        Owner::Bandits,
+        Unit {
+            unit_type: UnitType::Bandit,
+            swing_timer: Timer::from_seconds(5., TimerMode::Once),
+            color: PlayerColor::default(),
+        },
+        behaviour,
+        Health { hitpoints: 25. },
+        MeleeRange(10.),
+        Speed(30.),
+        Damage(10.),
+    )
+}
+
 fn meadow(
     mut commands: Commands,
     offset: Vec3,
@@ -149,24 +165,31 @@ fn meadow(
         game_scene_id,
     ));
     for i in 1..30 {
+        let behaviour = match i {
+            1..=12 => BanditBehaviour::Aggressive,
+            13..=20 => BanditBehaviour::Flee { threshold: 10. },
+            _ => BanditBehaviour::Ambush,
+        };
         commands.spawn((
-            Owner::Bandits,
-            Unit {
-                unit_type: UnitType::Bandit,
-                swing_timer: Timer::from_seconds(5., TimerMode::Once),
-                color: PlayerColor::default(),
-            },
-            BanditBehaviour::default(),
-            Health { hitpoints: 25. },
-            MeleeRange(10.),
-            Speed(30.),
-            Damage(10.),
+            bandit(behaviour),
             offset
                 .offset_x(150. - 10. * i as f32)
                 .with_layer(Layers::Unit),
             game_scene_id,
         ));
     }
+    for i in 0..4 {
+        commands.spawn((
+            bandit(BanditBehaviour::Patrol {
+                from: offset.x - 350.,
+                to: offset.x + 350.,
+            }),
+            offset
+                .offset_x(200. + 15. * i as f32)
+                .with_layer(Layers::Unit),
+            game_scene_id,
+        ));
+    }
     commands.entity(left_scene_end).insert((
         SceneEnd,
         TravelDestinationOffset::non_player(),
@@ -207,22 +230,32 @@ fn camp(
         game_scene_id,
     ));
     for i in 1..10 {
-        commands.spawn((
-            Owner::Bandits,
-            Unit {
-                unit_type: UnitType::Bandit,
-                swing_timer: Timer::from_seconds(5., TimerMode::Once),
-                color: PlayerColor::default(),
+        let behaviour = match i {
+            1..=5 => BanditBehaviour::Guard {
+                post: offset.x,
+                leash: 150.,
+            },
+            6..=7 => BanditBehaviour::Patrol {
+                from: offset.x - 400.,
+                to: offset.x + 400.,
             },
-            BanditBehaviour::default(),
-            Health { hitpoints: 25. },
-            MeleeRange(10.),
-            Speed(30.),
-            Damage(10.),
+            _ => BanditBehaviour::Flee { threshold: 10. },
+        };
+        commands.spawn((
+            bandit(behaviour),
             offset.offset_x(-10. * i as f32).with_layer(Layers::Unit),
             game_scene_id,
         ));
     }
+    for i in 0..5 {
+        commands.spawn((
+            bandit(BanditBehaviour::Ambush),
+            offset
+                .offset_x(-380. + 12. * i as f32)
+                .with_layer(Layers::Unit),
+            game_scene_id,
+        ));
+    }
     commands.entity(left_scene_end).insert((
         SceneEnd,
         TravelDestinationOffset::non_player(),
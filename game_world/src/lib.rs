@@ -2,17 +2,25 @@ use bevy::prelude::*;
 
 use bevy_replicon::prelude::AppRuleExt;
 use init_world::StartGamePlugin;
+use raid::RaidPlugin;
+use rival::RivalPlugin;
 use shared::GameScene;
 use world::WorldPlugin;
 
 pub mod init_world;
+pub mod raid;
+pub mod rival;
 pub mod world;
 
 pub struct GameWorldPlugin;
 
 impl Plugin for GameWorldPlugin {
     fn build(&self, app: &mut App) {
-        app.replicate::<GameScene>()
-            .add_plugins((StartGamePlugin, WorldPlugin));
+        app.replicate::<GameScene>().add_plugins((
+            StartGamePlugin,
+            WorldPlugin,
+            RivalPlugin,
+            RaidPlugin,
+        ));
     }
 }
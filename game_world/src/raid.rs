@@ -0,0 +1,240 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use bevy_replicon::prelude::{
+    Channel, ClientState, SendMode, ServerEventAppExt, ServerTriggerExt, ToClients,
+};
+use serde::{Deserialize, Serialize};
+use shared::{
+    ClientPlayerMap, ClientPlayerMapExt, GameScene, GameSceneId, GameState, Owner, Player,
+    SceneType, Vec3LayerExt,
+    map::{
+        Layers,
+        buildings::{Building, BuildingType},
+    },
+    networking::{Inventory, WorldDirection},
+    server::{
+        ai::BanditBehaviour,
+        entities::{Unit, health::Health},
+    },
+};
+use travel::{SceneEnd, TravelDestinations, Traveling};
+
+use super::{init_world::bandit, world::WorldGraph};
+
+/// How often the director considers sending raids.
+const RAID_INTERVAL: f32 = 60.;
+
+/// No raids are sent this early into the game.
+const RAID_GRACE_PERIOD: f32 = 300.;
+
+/// Raids on the same player are at least this far apart.
+const RAID_COOLDOWN: f32 = 240.;
+
+/// Chance of a raid on a player each time the director checks.
+const RAID_CHANCE: f32 = 0.35;
+
+/// Seconds a war party gathers next to the base before it travels there.
+const MUSTER_DURATION: f32 = 20.;
+
+/// Raiders added per minute of game time.
+const RAIDERS_PER_MINUTE: f32 = 0.5;
+
+/// Raiders added per gold of the targeted player.
+const RAIDERS_PER_GOLD: f32 = 0.01;
+
+/// Raiders added per unit of the targeted player.
+const RAIDERS_PER_UNIT: f32 = 0.25;
+
+const MIN_RAIDERS: usize = 3;
+const MAX_RAIDERS: usize = 20;
+
+pub struct RaidPlugin;
+
+impl Plugin for RaidPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RaidDirector>()
+            .add_server_event::<RaidWarning>(Channel::Ordered)
+            .add_systems(
+                FixedUpdate,
+                (direct_raids, march_raiders)
+                    .run_if(resource_exists::<WorldGraph>)
+                    .run_if(in_state(ClientState::Disconnected))
+                    .run_if(in_state(GameState::GameSession)),
+            );
+    }
+}
+
+/// Sent to a player whose base a bandit war party is gathering against.
+#[derive(Event, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct RaidWarning {
+    pub raiders: usize,
+    /// Seconds until the war party sets off towards the base.
+    pub departs_in: f32,
+}
+
+#[derive(Resource)]
+struct RaidDirector {
+    timer: Timer,
+    elapsed: f32,
+    /// Game time of the last raid on each player.
+    last_raid: HashMap<Entity, f32>,
+}
+
+impl Default for RaidDirector {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(RAID_INTERVAL, TimerMode::Repeating),
+            elapsed: 0.,
+            last_raid: HashMap::new(),
+        }
+    }
+}
+
+/// A bandit of a war party, gathering in `source` until it travels to the base in `target`.
+#[derive(Component)]
+struct Raider {
+    source: GameScene,
+    target: GameScene,
+    muster: Timer,
+}
+
+/// Size of a war party against a player, growing with game time and the player's wealth and army.
+fn raid_size(elapsed: f32, gold: u16, units: usize) -> usize {
+    let raiders = elapsed / 60. * RAIDERS_PER_MINUTE
+        + gold as f32 * RAIDERS_PER_GOLD
+        + units as f32 * RAIDERS_PER_UNIT;
+    (raiders as usize).min(MAX_RAIDERS)
+}
+
+fn direct_raids(
+    mut director: ResMut<RaidDirector>,
+    players: Query<(Entity, &Inventory), With<Player>>,
+    units: Query<&Owner, (With<Unit>, With<Health>)>,
+    scene_ends: Query<(&Transform, &GameScene, &TravelDestinations), With<SceneEnd>>,
+    map: Res<WorldGraph>,
+    client_player_map: Res<ClientPlayerMap>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    director.elapsed += time.delta_secs();
+    if !director.timer.tick(time.delta()).just_finished() || director.elapsed < RAID_GRACE_PERIOD {
+        return;
+    }
+    let elapsed = director.elapsed;
+
+    for base in map.node_indices() {
+        let SceneType::Player { player, .. } = map[base].scene else {
+            continue;
+        };
+        let Ok((_, inventory)) = players.get(player) else {
+            continue;
+        };
+
+        let last_raid = director.last_raid.get(&player).copied();
+        if last_raid.is_some_and(|last_raid| elapsed - last_raid < RAID_COOLDOWN)
+            || fastrand::f32() > RAID_CHANCE
+        {
+            continue;
+        }
+
+        let army = units
+            .iter()
+            .filter(|owner| **owner == Owner::Player(player))
+            .count();
+        let raiders = raid_size(elapsed, inventory.gold, army);
+        if raiders < MIN_RAIDERS {
+            continue;
+        }
+
+        // Gather at the end of a neighbouring scene that leads to the base.
+        let target = map[base];
+        let neighbours: Vec<GameSceneId> = map.neighbors(base).map(|node| map[node].id).collect();
+        let Some(source_id) = fastrand::choice(neighbours) else {
+            continue;
+        };
+        let Some((end_transform, source, _)) =
+            scene_ends.iter().find(|(_, scene, destinations)| {
+                scene.id == source_id && destinations.contains(&target.entry_entity())
+            })
+        else {
+            continue;
+        };
+
+        for i in 0..raiders {
+            let x = end_transform.translation.x + (fastrand::f32() - 0.5) * 60.;
+            commands.spawn((
+                bandit(BanditBehaviour::Guard {
+                    post: x,
+                    leash: 100.,
+                }),
+                Raider {
+                    source: *source,
+                    target,
+                    muster: Timer::from_seconds(MUSTER_DURATION + i as f32 * 0.2, TimerMode::Once),
+                },
+                end_transform
+                    .translation
+                    .with_x(x)
+                    .with_y(0.)
+                    .with_layer(Layers::Unit),
+                source_id,
+            ));
+        }
+
+        director.last_raid.insert(player, elapsed);
+        info!(
+            "{} raiders gathering in {:?} against {:?}.",
+            raiders, source_id, player
+        );
+
+        if let Ok(client) = client_player_map.get_network_entity(&player) {
+            commands.server_trigger(ToClients {
+                mode: SendMode::Direct(*client),
+                message: RaidWarning {
+                    raiders,
+                    departs_in: MUSTER_DURATION,
+                },
+            });
+        }
+    }
+}
+
+fn march_raiders(
+    mut raiders: Query<(Entity, &mut Raider, &Transform, Option<&GameSceneId>), Without<Traveling>>,
+    buildings: Query<(&Building, &Transform, &GameSceneId)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut raider, transform, game_scene_id) in raiders.iter_mut() {
+        let Some(game_scene_id) = game_scene_id else {
+            continue;
+        };
+
+        // Arrived at the base, march on its main building.
+        if *game_scene_id == raider.target.id {
+            let main_building = buildings.iter().find(|(building, _, building_scene_id)| {
+                *building_scene_id == game_scene_id
+                    && matches!(building.building_type, BuildingType::MainBuilding { .. })
+            });
+            let direction =
+                main_building.map_or(WorldDirection::Left, |(_, building_transform, _)| {
+                    (building_transform.translation.x - transform.translation.x).into()
+                });
+
+            commands
+                .entity(entity)
+                .remove::<Raider>()
+                .insert(BanditBehaviour::Raid(direction));
+            continue;
+        }
+
+        if !raider.muster.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .insert(Traveling::between(raider.source, raider.target))
+            .remove::<GameSceneId>();
+    }
+}
@@ -0,0 +1,708 @@
+use bevy::prelude::*;
+
+use bevy_replicon::{
+    prelude::{AppRuleExt, ClientId, ClientState, FromClient},
+    server::ServerSystems,
+};
+use petgraph::algo::astar;
+use serde::{Deserialize, Serialize};
+use shared::{
+    GameScene, GameSceneId, GameState, Owner, Player, PlayerColor, SceneType,
+    enum_map::EnumIter,
+    map::{
+        Layers,
+        buildings::{BuildStatus, Building, BuildingType, RecruitBuilding},
+    },
+    networking::{Difficulty, Inventory, LobbyMessage, UnitType, WorldDirection},
+    player_attacks::give_order,
+    server::{
+        ai::UnitBehaviour,
+        buildings::{
+            item_assignment::{BuildAssignment, ItemAssignment},
+            recruiting::{Flag, FlagAssignment, FlagHolder, FlagUnits},
+        },
+        entities::{
+            Unit,
+            commander::{ArmyFlagAssignments, ArmyPosition, Assignment},
+            health::Health,
+        },
+        physics::movement::{Speed, Velocity},
+        players::{
+            chest::{Chest, ChestOpened},
+            flag::FlagDestroyed,
+            interaction::{InteractionTriggeredEvent, InteractionType},
+            items::Item,
+            loot::GoldPile,
+            team::Factions,
+        },
+    },
+};
+use travel::{SceneEnd, StartTravel, TravelDestinations, Traveling};
+
+use super::world::{InitWorld, WorldGraph};
+
+/// How close a rival king has to walk to an entity to interact with it.
+const INTERACTION_DISTANCE: f32 = 15.;
+
+pub struct RivalPlugin;
+
+impl Plugin for RivalPlugin {
+    fn build(&self, app: &mut App) {
+        app.replicate::<RivalKing>()
+            .add_observer(init_rivals)
+            .add_systems(
+                PreUpdate,
+                lobby_rivals
+                    .after(ServerSystems::Receive)
+                    .run_if(in_state(ClientState::Disconnected))
+                    .run_if(in_state(GameState::MainMenu)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (plan_rivals, perform_rival_task)
+                    .chain()
+                    .run_if(resource_exists::<WorldGraph>)
+                    .run_if(in_state(ClientState::Disconnected))
+                    .run_if(in_state(GameState::GameSession)),
+            );
+    }
+}
+
+/// A computer controlled king occupying a player slot.
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
+#[require(RivalCampaign)]
+pub struct RivalKing {
+    pub difficulty: Difficulty,
+}
+
+/// Time until the rival king makes its next decision.
+#[derive(Component, Deref, DerefMut)]
+struct RivalTimer(Timer);
+
+/// The scene of the rival king's base.
+#[derive(Component, Deref)]
+struct RivalHome(GameSceneId);
+
+/// Scenes the rival king has cleared of enemies and loot.
+#[derive(Component, Default)]
+struct RivalCampaign {
+    cleared: Vec<GameSceneId>,
+}
+
+/// Something the rival king walks to and then does.
+#[derive(Component)]
+struct RivalTask {
+    target: Entity,
+    action: RivalAction,
+}
+
+enum RivalAction {
+    Interact(InteractionType),
+    AssignItems,
+    JoinArmy(ArmyPosition),
+    Travel(GameScene),
+}
+
+trait DifficultyExt {
+    /// Seconds between two decisions.
+    fn decision_interval(&self) -> f32;
+    /// Living flags in the army before it leaves the base.
+    fn army_flags(&self) -> usize;
+    /// Units left in the army at which it returns home.
+    fn retreat_below(&self) -> usize;
+    /// Bandit camps cleared before attacking other players.
+    fn camps_before_attack(&self) -> usize;
+    fn upgrades_buildings(&self) -> bool;
+}
+
+impl DifficultyExt for Difficulty {
+    fn decision_interval(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 4.,
+            Difficulty::Normal => 2.,
+            Difficulty::Hard => 1.,
+        }
+    }
+
+    fn army_flags(&self) -> usize {
+        match self {
+            Difficulty::Easy => 1,
+            Difficulty::Normal => 2,
+            Difficulty::Hard => ArmyPosition::COUNT,
+        }
+    }
+
+    fn retreat_below(&self) -> usize {
+        match self {
+            Difficulty::Easy => 4,
+            Difficulty::Normal => 3,
+            Difficulty::Hard => 2,
+        }
+    }
+
+    fn camps_before_attack(&self) -> usize {
+        match self {
+            Difficulty::Easy => 3,
+            Difficulty::Normal => 2,
+            Difficulty::Hard => 1,
+        }
+    }
+
+    fn upgrades_buildings(&self) -> bool {
+        match self {
+            Difficulty::Easy => false,
+            Difficulty::Normal | Difficulty::Hard => true,
+        }
+    }
+}
+
+fn lobby_rivals(
+    mut lobby_events: MessageReader<FromClient<LobbyMessage>>,
+    rivals: Query<Entity, With<RivalKing>>,
+    players: Query<&Player>,
+    mut commands: Commands,
+) {
+    // Colors of rivals added this tick are not queryable yet.
+    let mut taken: Vec<PlayerColor> = players.iter().map(|player| player.color).collect();
+
+    for FromClient { client_id, message } in lobby_events.read() {
+        // Only the host fills the lobby with rivals.
+        if *client_id != ClientId::Server {
+            continue;
+        }
+
+        match message {
+            LobbyMessage::StartGame | LobbyMessage::CycleTeam => {}
+            LobbyMessage::AddRival(difficulty) => {
+                let color = PlayerColor::unused(taken.iter().copied());
+                taken.push(color);
+                let rival = commands.spawn_empty().id();
+                commands.entity(rival).insert((
+                    Player {
+                        id: rival.to_bits(),
+                        color,
+                    },
+                    RivalKing {
+                        difficulty: *difficulty,
+                    },
+                    RivalTimer(Timer::from_seconds(
+                        difficulty.decision_interval(),
+                        TimerMode::Repeating,
+                    )),
+                    Transform::from_xyz(250.0, 0.0, Layers::Player.as_f32()),
+                    Owner::Player(rival),
+                    Health { hitpoints: 200. },
+                    GameSceneId::lobby(),
+                ));
+                info!("Rival {:?} added with difficulty {:?}.", rival, difficulty);
+            }
+            LobbyMessage::RemoveRival => {
+                if let Some(rival) = rivals.iter().max() {
+                    commands.entity(rival).despawn();
+                }
+            }
+        }
+    }
+}
+
+fn init_rivals(
+    init_world: On<InitWorld>,
+    rivals: Query<(), With<RivalKing>>,
+    mut commands: Commands,
+) {
+    let world = &**init_world.event();
+
+    for game_scene in world.node_weights() {
+        if let SceneType::Player { player, .. } = game_scene.scene
+            && rivals.contains(player)
+        {
+            commands.entity(player).insert(RivalHome(game_scene.id));
+        }
+    }
+}
+
+/// The next scene on the shortest path from `from` to `to`, and the number of hops to `to`.
+fn route(map: &WorldGraph, from: GameSceneId, to: GameSceneId) -> Option<(GameScene, usize)> {
+    let index = |id: GameSceneId| map.node_indices().find(|node| map[*node].id == id);
+    let goal = index(to)?;
+    let (hops, path) = astar(
+        &**map,
+        index(from)?,
+        |node| node == goal,
+        |_| 1_usize,
+        |_| 0,
+    )?;
+    path.get(1).map(|next| (map[*next], hops))
+}
+
+fn plan_rivals(
+    mut rivals: Query<
+        (
+            Entity,
+            &RivalKing,
+            &mut RivalTimer,
+            &RivalHome,
+            &mut RivalCampaign,
+            &Transform,
+            &Inventory,
+            &GameSceneId,
+            Option<&FlagHolder>,
+        ),
+        (With<Health>, Without<RivalTask>, Without<Traveling>),
+    >,
+    humans: Query<(), (With<Player>, Without<RivalKing>)>,
+    units: Query<(&Owner, &Transform, &GameSceneId), (With<Unit>, With<Health>)>,
+    commanders: Query<(Entity, &Unit, &Owner, &FlagAssignment), With<Health>>,
+    behaviours: Query<&UnitBehaviour>,
+    flags: Query<(&Flag, Has<FlagDestroyed>)>,
+    flag_units: Query<&FlagUnits>,
+    army: Query<&ArmyFlagAssignments>,
+    transforms: Query<&Transform>,
+    buildings: Query<(
+        Entity,
+        &Building,
+        &BuildStatus,
+        &Owner,
+        &Transform,
+        &GameSceneId,
+        Has<RecruitBuilding>,
+    )>,
+    assignments: Query<(Entity, &ItemAssignment, &Owner, &GameSceneId), Without<Building>>,
+    loot: Query<
+        (Entity, &Transform, &GameSceneId, Has<Chest>, Has<GoldPile>),
+        (
+            Or<(With<Item>, With<Chest>, With<GoldPile>)>,
+            Without<ChestOpened>,
+        ),
+    >,
+    scene_ends: Query<(Entity, &GameSceneId, &TravelDestinations), With<SceneEnd>>,
+    factions: Factions,
+    map: Res<WorldGraph>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (
+        rival,
+        king,
+        mut timer,
+        home,
+        mut campaign,
+        transform,
+        inventory,
+        game_scene_id,
+        flag_holder,
+    ) in rivals.iter_mut()
+    {
+        if !timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let difficulty = king.difficulty;
+        let owner = Owner::Player(rival);
+        let x = transform.translation.x;
+        let nearest = |entity_x: f32| (entity_x - x).abs();
+
+        let commander = commanders.iter().find(|(_, unit, unit_owner, _)| {
+            **unit_owner == owner && unit.unit_type == UnitType::Commander
+        });
+        // A stale entity only skips this rival, the others still get to plan.
+        let held_flag = match flag_holder {
+            Some(flag_holder) => {
+                let Ok(flag) = flags.get(**flag_holder) else {
+                    continue;
+                };
+                Some((**flag_holder, flag))
+            }
+            None => None,
+        };
+        let army_units = units
+            .iter()
+            .filter(|(unit_owner, _, unit_scene_id)| {
+                **unit_owner == owner && *unit_scene_id == game_scene_id
+            })
+            .count();
+
+        // Lead the held flag against the nearest enemy in the scene.
+        let nearest_enemy = units
+            .iter()
+            .filter(|(unit_owner, _, unit_scene_id)| {
+                factions.is_enemy(unit_owner, &owner) && *unit_scene_id == game_scene_id
+            })
+            .map(|(_, unit_transform, _)| unit_transform.translation.x)
+            .chain(
+                buildings
+                    .iter()
+                    .filter(|(_, _, status, building_owner, _, building_scene_id, _)| {
+                        factions.is_enemy(building_owner, &owner)
+                            && *building_scene_id == game_scene_id
+                            && matches!(status, BuildStatus::Built { .. })
+                    })
+                    .map(|(.., building_transform, _, _)| building_transform.translation.x),
+            )
+            .min_by(|a, b| nearest(*a).total_cmp(&nearest(*b)));
+
+        if let Some((flag, (_, false))) = held_flag
+            && let Ok(units) = flag_units.get(flag)
+            && let Some(unit) = units.iter().next()
+            && let Ok(behaviour) = behaviours.get(unit)
+        {
+            let new_behaviour = match (nearest_enemy, behaviour) {
+                (Some(enemy_x), UnitBehaviour::Charge(direction))
+                    if *direction == WorldDirection::from(enemy_x - x) =>
+                {
+                    None
+                }
+                (Some(enemy_x), _) => Some(UnitBehaviour::Charge((enemy_x - x).into())),
+                (None, UnitBehaviour::FollowFlag) => None,
+                (None, _) => Some(UnitBehaviour::FollowFlag),
+            };
+            if let Some(new_behaviour) = new_behaviour
+                && give_order(
+                    flag,
+                    unit,
+                    new_behaviour,
+                    &flag_units,
+                    &army,
+                    &transforms,
+                    &mut commands,
+                )
+                .is_err()
+            {
+                continue;
+            }
+            if nearest_enemy.is_some() {
+                continue;
+            }
+        }
+
+        let travel_to = |to: GameSceneId| {
+            let (next, _) = route(&map, *game_scene_id, to)?;
+            scene_ends
+                .iter()
+                .find(|(_, scene_id, destinations)| {
+                    *scene_id == game_scene_id && destinations.contains(&next.entry_entity())
+                })
+                .map(|(scene_end, ..)| RivalTask {
+                    target: scene_end,
+                    action: RivalAction::Travel(next),
+                })
+        };
+
+        let task = 'plan: {
+            if let Some((flag, (_, true))) = held_flag {
+                break 'plan Some(RivalTask {
+                    target: flag,
+                    action: RivalAction::Interact(InteractionType::Flag),
+                });
+            }
+
+            if *game_scene_id != **home {
+                if army_units < difficulty.retreat_below() {
+                    break 'plan travel_to(**home);
+                }
+
+                let nearest_loot = loot
+                    .iter()
+                    .filter(|(_, _, loot_scene_id, ..)| *loot_scene_id == game_scene_id)
+                    .min_by(|(_, a, ..), (_, b, ..)| {
+                        nearest(a.translation.x).total_cmp(&nearest(b.translation.x))
+                    });
+                if let Some((loot, _, _, is_chest, is_gold)) = nearest_loot {
+                    let kind = match (is_chest, is_gold) {
+                        (true, _) => InteractionType::Chest,
+                        (_, true) => InteractionType::Gold,
+                        _ => InteractionType::Item,
+                    };
+                    break 'plan Some(RivalTask {
+                        target: loot,
+                        action: RivalAction::Interact(kind),
+                    });
+                }
+
+                if !campaign.cleared.contains(game_scene_id) {
+                    campaign.cleared.push(*game_scene_id);
+                }
+                let target =
+                    campaign_target(rival, &campaign, difficulty, &map, &humans, &factions);
+                break 'plan travel_to(target.unwrap_or(**home));
+            }
+
+            let gold = inventory.gold;
+            let own_buildings = || {
+                buildings.iter().filter(
+                    |(_, _, status, building_owner, _, building_scene_id, _)| {
+                        **building_owner == owner
+                            && *building_scene_id == game_scene_id
+                            && matches!(status, BuildStatus::Built { .. })
+                    },
+                )
+            };
+            let main_building = own_buildings()
+                .find(|(_, building, ..)| {
+                    matches!(building.building_type, BuildingType::MainBuilding { .. })
+                })
+                .map(|(main_building, ..)| main_building);
+            let unit_building = own_buildings()
+                .filter(|(_, building, .., is_recruit_building)| {
+                    *is_recruit_building
+                        && building.unit_type().is_some_and(|unit_type| {
+                            unit_type != UnitType::Commander
+                                && gold >= unit_type.recruitment_cost().gold
+                        })
+                })
+                .min_by(|(_, _, _, _, a, ..), (_, _, _, _, b, ..)| {
+                    nearest(a.translation.x).total_cmp(&nearest(b.translation.x))
+                })
+                .map(|(unit_building, ..)| unit_building);
+            let can_recruit_commander =
+                main_building.is_some() && gold >= UnitType::Commander.recruitment_cost().gold;
+
+            let army_flags = commander.and_then(|(commander, ..)| army.get(commander).ok());
+            let alive_army_flags = army_flags.map_or(0, |army_flags| {
+                army_flags
+                    .flags
+                    .iter()
+                    .flatten()
+                    .filter(|flag| flags.get(**flag).is_ok_and(|(_, destroyed)| !destroyed))
+                    .count()
+            });
+            let army_ready = alive_army_flags >= difficulty.army_flags();
+            let empty_slot = army_flags.and_then(|army_flags| {
+                army_flags
+                    .flags
+                    .iter_enums()
+                    .find(|(_, flag)| {
+                        flag.is_none_or(|flag| {
+                            flags.get(flag).is_ok_and(|(_, destroyed)| destroyed)
+                        })
+                    })
+                    .map(|(slot, _)| slot)
+            });
+
+            // Equip the empty unit buildings of the base.
+            let equippable =
+                assignments
+                    .iter()
+                    .find(|(_, assignment, building_owner, building_scene_id)| {
+                        **building_owner == owner
+                            && *building_scene_id == game_scene_id
+                            && assignment.items.iter_enums().all(|(slot, item)| {
+                                item.is_some()
+                                    || inventory.items.iter().any(|item| item.slot() == slot)
+                            })
+                    });
+            if let Some((building, ..)) = equippable {
+                break 'plan Some(RivalTask {
+                    target: building,
+                    action: RivalAction::AssignItems,
+                });
+            }
+
+            let interact = |target: Entity, kind: InteractionType| RivalTask {
+                target,
+                action: RivalAction::Interact(kind),
+            };
+            let recruit = |building: Entity| interact(building, InteractionType::Recruit);
+            let toggle_flag = |flag: Entity| interact(flag, InteractionType::Flag);
+
+            match (held_flag, commander) {
+                (Some((flag, (held, _))), Some((commander, ..))) => {
+                    if held.unit_type != UnitType::Commander {
+                        break 'plan Some(match empty_slot {
+                            Some(slot) => RivalTask {
+                                target: commander,
+                                action: RivalAction::JoinArmy(slot),
+                            },
+                            None => toggle_flag(flag),
+                        });
+                    }
+                    if !army_ready && unit_building.is_some() {
+                        break 'plan Some(toggle_flag(flag));
+                    }
+                }
+                (Some((flag, _)), None) => {
+                    if can_recruit_commander {
+                        break 'plan Some(toggle_flag(flag));
+                    }
+                }
+                (None, None) => {
+                    if can_recruit_commander {
+                        break 'plan main_building.map(recruit);
+                    }
+                    if let Some(unit_building) = unit_building {
+                        break 'plan Some(recruit(unit_building));
+                    }
+                }
+                (None, Some((_, _, _, commander_flag))) => {
+                    if !army_ready && let Some(unit_building) = unit_building {
+                        break 'plan Some(recruit(unit_building));
+                    }
+                    break 'plan Some(toggle_flag(**commander_flag));
+                }
+            }
+
+            // Spend what is left on the base, keeping enough gold to replace the commander.
+            if difficulty.upgrades_buildings() {
+                let reserve = UnitType::Commander.recruitment_cost().gold;
+                let upgrade = buildings
+                    .iter()
+                    .filter(|(_, _, _, building_owner, _, building_scene_id, _)| {
+                        **building_owner == owner && *building_scene_id == game_scene_id
+                    })
+                    .find(|(_, building, status, ..)| {
+                        build_cost(building, status)
+                            .is_some_and(|cost| gold >= cost.saturating_add(reserve))
+                    });
+                if let Some((building, ..)) = upgrade {
+                    break 'plan Some(interact(building, InteractionType::Building));
+                }
+            }
+
+            if army_ready && army_units >= 2 * difficulty.retreat_below() {
+                break 'plan campaign_target(
+                    rival, &campaign, difficulty, &map, &humans, &factions,
+                )
+                .and_then(travel_to);
+            }
+            None
+        };
+
+        if let Some(task) = task {
+            commands.entity(rival).insert(task);
+        }
+    }
+}
+
+/// Gold needed to build, rebuild or upgrade `building`, if any of it is possible.
+fn build_cost(building: &Building, status: &BuildStatus) -> Option<u16> {
+    match status {
+        BuildStatus::Marker | BuildStatus::Destroyed => Some(building.cost().gold),
+        BuildStatus::Built { .. } => building
+            .upgrade_building()
+            .map(|upgrade| upgrade.cost().gold),
+        BuildStatus::Constructing => None,
+    }
+}
+
+/// The scene the rival king's army heads to next. Bandit camps come first until enough are
+/// cleared, then the bases of players it isn't allied with, humans before other rivals.
+fn campaign_target(
+    rival: Entity,
+    campaign: &RivalCampaign,
+    difficulty: Difficulty,
+    map: &WorldGraph,
+    humans: &Query<(), (With<Player>, Without<RivalKing>)>,
+    factions: &Factions,
+) -> Option<GameSceneId> {
+    let is_camp = |scene: &GameScene| matches!(scene.scene, SceneType::Camp { .. });
+    let cleared_camps = map
+        .node_weights()
+        .filter(|scene| is_camp(scene) && campaign.cleared.contains(&scene.id))
+        .count();
+    let attacking = cleared_camps >= difficulty.camps_before_attack();
+
+    let home = map
+        .node_weights()
+        .find(|scene| matches!(scene.scene, SceneType::Player { player, .. } if player == rival))?;
+
+    map.node_weights()
+        .filter(|scene| !campaign.cleared.contains(&scene.id))
+        .filter_map(|scene| {
+            let priority = match scene.scene {
+                SceneType::Camp { .. } if attacking => 2,
+                SceneType::Camp { .. } => 0,
+                SceneType::Player { player, .. } if factions.shares_with(player, rival) => {
+                    return None;
+                }
+                SceneType::Player { player, .. } if !attacking => {
+                    3 + usize::from(!humans.contains(player))
+                }
+                SceneType::Player { player, .. } => usize::from(!humans.contains(player)),
+                SceneType::Meadow { .. } => return None,
+            };
+            let (_, hops) = route(map, home.id, scene.id)?;
+            Some((priority, hops, scene.id))
+        })
+        .min_by_key(|(priority, hops, _)| (*priority, *hops))
+        .map(|(.., scene_id)| scene_id)
+}
+
+#[allow(clippy::type_complexity)]
+fn perform_rival_task(
+    mut rivals: Query<(
+        Entity,
+        &RivalTask,
+        &Transform,
+        &GameSceneId,
+        &Speed,
+        &mut Velocity,
+        &mut Inventory,
+    )>,
+    targets: Query<(&Transform, &GameSceneId)>,
+    game_scenes: Query<&GameScene>,
+    mut assignments: Query<&mut ItemAssignment>,
+    mut interactions: MessageWriter<InteractionTriggeredEvent>,
+    mut commands: Commands,
+) -> Result {
+    for (rival, task, transform, game_scene_id, speed, mut velocity, mut inventory) in
+        rivals.iter_mut()
+    {
+        let target = targets.get(task.target);
+        let Ok((target_transform, target_scene_id)) = target else {
+            velocity.0.x = 0.;
+            commands.entity(rival).remove::<RivalTask>();
+            continue;
+        };
+        if target_scene_id != game_scene_id {
+            velocity.0.x = 0.;
+            commands.entity(rival).remove::<RivalTask>();
+            continue;
+        }
+
+        let distance = target_transform.translation.x - transform.translation.x;
+        if distance.abs() > INTERACTION_DISTANCE {
+            velocity.0.x = distance.signum() * **speed;
+            continue;
+        }
+        velocity.0.x = 0.;
+        commands.entity(rival).remove::<RivalTask>();
+
+        match task.action {
+            RivalAction::Interact(interaction) => {
+                interactions.write(InteractionTriggeredEvent {
+                    player: rival,
+                    interactable: task.target,
+                    interaction,
+                });
+            }
+            RivalAction::AssignItems => {
+                let mut assignment = assignments.get_mut(task.target)?;
+                for (slot, assigned) in assignment.items.iter_enums_mut() {
+                    if assigned.is_some() {
+                        continue;
+                    }
+                    if let Some(index) = inventory.items.iter().position(|item| item.slot() == slot)
+                    {
+                        *assigned = Some(inventory.items.remove(index));
+                    }
+                }
+                commands.trigger(BuildAssignment {
+                    player: rival,
+                    building: task.target,
+                });
+            }
+            RivalAction::JoinArmy(slot) => {
+                commands.trigger(Assignment::new(rival, task.target, slot));
+            }
+            RivalAction::Travel(target) => {
+                let source = *game_scenes.get(task.target)?;
+                commands.trigger(StartTravel {
+                    player: rival,
+                    source,
+                    target,
+                });
+            }
+        }
+    }
+    Ok(())
+}
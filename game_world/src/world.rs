@@ -29,7 +29,8 @@ impl Plugin for WorldPlugin {
 #[derive(Event, Deref)]
 pub struct InitWorld(WorldGraph);
 
-#[derive(Clone, Default, Deref, DerefMut)]
+/// The scenes of the running game and the roads between them.
+#[derive(Resource, Clone, Default, Deref, DerefMut)]
 pub struct WorldGraph(Graph<GameScene, (), Undirected>);
 
 #[derive(Default, Clone, Deref)]
@@ -180,14 +181,12 @@ fn init_world(
     client_player_map: Res<ClientPlayerMap>,
     mut commands: Commands,
 ) -> Result {
-    let Some(FromClient { message, .. }) = lobby_events.read().next() else {
+    let start_game = lobby_events
+        .read()
+        .any(|FromClient { message, .. }| matches!(message, LobbyMessage::StartGame));
+    if !start_game {
         return Ok(());
-    };
-
-    #[allow(irrefutable_let_patterns)]
-    let LobbyMessage::StartGame = message else {
-        return Ok(());
-    };
+    }
 
     let players: Vec<Entity> = players.iter().collect();
     let num_players = players.len();
@@ -197,6 +196,7 @@ fn init_world(
         100. + 25. * num_players as f32,
     );
 
+    commands.insert_resource(map.clone());
     commands.trigger(InitWorld(map));
 
     for (client, player) in client_player_map.iter() {
@@ -0,0 +1,56 @@
+use bevy_remote::BrpRequest;
+use serde_json::Value;
+use std::{
+    io::{BufRead, BufReader},
+    ops::ControlFlow,
+};
+use ureq::Agent;
+
+/// Keeps the HTTP connection to the game alive between requests.
+pub struct Connection {
+    agent: Agent,
+    url: String,
+}
+
+impl Connection {
+    pub fn new(host: &str, port: u16) -> Self {
+        Self {
+            agent: Agent::new_with_defaults(),
+            url: format!("http://{host}:{port}/"),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn send(&self, request: BrpRequest) -> Result<Value, ureq::Error> {
+        let mut response = self.agent.post(&self.url).send_json(request)?;
+        response.body_mut().read_json::<Value>()
+    }
+
+    /// Sends a `+watch` request and hands every streamed response to `on_message` until the
+    /// game closes the stream or `on_message` breaks.
+    pub fn stream(
+        &self,
+        request: BrpRequest,
+        mut on_message: impl FnMut(Value) -> ControlFlow<()>,
+    ) -> Result<(), ureq::Error> {
+        let response = self.agent.post(&self.url).send_json(request)?;
+        let reader = BufReader::new(response.into_body().into_reader());
+
+        for line in reader.lines() {
+            let line = line?;
+            let Some(data) = line.strip_prefix("data: ") else {
+                continue;
+            };
+            let Ok(message) = serde_json::from_str(data) else {
+                continue;
+            };
+            if on_message(message).is_break() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
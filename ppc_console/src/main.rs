@@ -1,8 +1,14 @@
 use bevy_remote::{BrpRequest, http::DEFAULT_ADDR, http::DEFAULT_PORT};
-use clap::{Parser, Subcommand, ValueHint, arg, command};
+use clap::{Parser, Subcommand, ValueHint};
+use connection::Connection;
 use console_protocol::*;
 use dialoguer::Select;
-use serde_json::{Value, to_value};
+use std::path::PathBuf;
+
+mod connection;
+mod repl;
+mod script;
+mod watch;
 
 #[derive(Parser)]
 #[command(name = "ppc", about = "Cheat console for warppcs.", version = "0.1.0")]
@@ -19,98 +25,85 @@ pub struct PPC {
 
 #[derive(Subcommand)]
 pub enum PPCSubCommands {
-    RandomItems {
-        #[arg(short, long, value_hint = ValueHint::CommandWithArguments, default_value_t = 0)]
-        player: u8,
+    /// Start an interactive session with history and tab completion.
+    Repl,
+    /// Execute a script of commands, separated by newlines or `;`.
+    Run {
+        #[arg(value_hint = ValueHint::FilePath)]
+        script: PathBuf,
     },
-    SpawnUnit {
-        #[arg(short, long, value_hint = ValueHint::CommandWithArguments)]
-        unit: Option<String>,
+    /// Print gameplay events as they happen.
+    Watch {
+        /// Only show events involving this player.
+        #[arg(short, long)]
+        player: Option<PlayerSelector>,
 
-        #[arg(short, long, value_hint = ValueHint::CommandWithArguments, default_value_t = 0)]
-        player: u8,
-    },
-    SpawnFullCommander {
-        #[arg(short, long, value_hint = ValueHint::CommandWithArguments, default_value_t = 0)]
-        player: u8,
-    },
-    SpawnUnitAndBandits {
-        #[arg(short, long, value_hint = ValueHint::CommandWithArguments, default_value_t = 0)]
-        player: u8,
+        /// Only show events inside this game scene.
+        #[arg(short, long)]
+        scene: Option<usize>,
     },
+    #[command(flatten)]
+    Console(ConsoleCommand),
 }
 
 fn main() {
     let cli = PPC::parse();
 
-    let host_part = format!("{DEFAULT_ADDR}:{DEFAULT_PORT}");
-    let url = format!("http://{host_part}/");
-
-    let request = match cli.command {
-        PPCSubCommands::RandomItems { player } => BrpRequest {
-            jsonrpc: String::from("2.0"),
-            method: BRP_SPAWN_RANDOM_ITEM.into(),
-            id: None,
-            params: Some(
-                to_value(BrpSpawnItems { player })
-                    .expect("Unable to convert query parameters to a valid JSON value"),
-            ),
-        },
-        PPCSubCommands::SpawnUnit { unit, player } => {
-            let unit_spawn = match unit {
-                Some(unit) => unit,
-                None => {
-                    let items = vec!["archer", "pikemen", "shield"];
-                    let selection = Select::new()
-                        .with_prompt("Which unit?")
-                        .items(&items)
-                        .interact()
-                        .unwrap();
-
-                    items[selection].to_string()
-                }
-            };
+    let connection = Connection::new(&cli.host, cli.port);
 
-            BrpRequest {
-                jsonrpc: String::from("2.0"),
-                method: BRP_SPAWN_UNIT.into(),
-                id: None,
-                params: Some(
-                    to_value(BrpSpawnUnit {
-                        unit: unit_spawn,
-                        player,
-                    })
-                    .expect("Unable to convert query parameters to a valid JSON value"),
-                ),
+    match cli.command {
+        PPCSubCommands::Repl => repl::run(&connection),
+        PPCSubCommands::Run { script } => {
+            if let Err(error) = script::run_file(&connection, &script) {
+                println!("{error}");
             }
         }
-        PPCSubCommands::SpawnFullCommander { player } => BrpRequest {
-            jsonrpc: String::from("2.0"),
-            method: BRP_SPAWN_FULL_COMMANDER.into(),
-            id: None,
-            params: Some(
-                to_value(BrpSpawnFullCommander { player })
-                    .expect("Unable to convert query parameters to a valid JSON value"),
-            ),
-        },
-        PPCSubCommands::SpawnUnitAndBandits { player } => BrpRequest {
-            jsonrpc: String::from("2.0"),
-            method: BRP_SPAWN_UNIT_AND_BANDITS.into(),
-            id: None,
-            params: Some(
-                to_value(BrpSpawnUnitAndBandits { player })
-                    .expect("Unable to convert query parameters to a valid JSON value"),
-            ),
-        },
-    };
+        PPCSubCommands::Watch { player, scene } => {
+            watch::run(&connection, BrpWatchEvents { player, scene })
+        }
+        PPCSubCommands::Console(command) => execute(&connection, command),
+    }
+}
 
-    let maybe_response = ureq::post(&url).send_json(request);
+/// Builds the BRP request, asking for the unit if `spawn-unit` was called without one.
+pub fn request(mut command: ConsoleCommand) -> Result<BrpRequest, String> {
+    if let ConsoleCommand::SpawnUnit {
+        unit: unit @ None, ..
+    } = &mut command
+    {
+        let selection = Select::new()
+            .with_prompt("Which unit?")
+            .items(&UNITS)
+            .interact()
+            .unwrap();
 
-    match maybe_response {
-        Ok(mut body) => {
-            let response = body.body_mut().read_json::<Value>().unwrap();
-            println!("{response:#}");
+        *unit = Some(UNITS[selection].to_string());
+    }
+
+    Ok(BrpRequest {
+        jsonrpc: String::from("2.0"),
+        method: command.method().into(),
+        id: None,
+        params: command.params()?,
+    })
+}
+
+/// Sends the command and prints the response.
+pub fn execute(connection: &Connection, command: ConsoleCommand) {
+    let request = match request(command) {
+        Ok(request) => request,
+        Err(error) => {
+            println!("{error}");
+            return;
         }
-        Err(_) => println!("No running bevy application found."),
+    };
+    let method = request.method.clone();
+
+    match connection.send(request) {
+        Ok(response) => match response.get("result") {
+            Some(result) => println!("{}", format_result(&method, result)),
+            None => println!("{response:#}"),
+        },
+        Err(_) => println!("No running bevy application found at {}.", connection.url()),
     }
 }
@@ -0,0 +1,153 @@
+use console_protocol::{BrpPlayerInfo, ConsoleCommand, command_names, complete};
+use dialoguer::{Completion, History, Input};
+use serde_json::from_value;
+use std::{collections::VecDeque, fs, path::PathBuf};
+
+use crate::{
+    connection::Connection,
+    request,
+    script::{self, SessionCommand, SessionLine},
+};
+
+const MAX_HISTORY: usize = 500;
+
+pub fn run(connection: &Connection) {
+    println!(
+        "Connected to {}. Type `help` for commands, `exit` to quit.",
+        connection.url()
+    );
+
+    let mut history = FileHistory::load();
+    let mut completion = ReplCompletion::new(connection);
+
+    while let Ok(line) = prompt(&mut history, &completion) {
+        if let "exit" | "quit" = line.trim() {
+            break;
+        }
+
+        for (_, statement) in script::statements(&line) {
+            let command = match script::parse(statement) {
+                Ok(command) => command,
+                Err(error) => {
+                    println!("{error}");
+                    continue;
+                }
+            };
+
+            let refresh_players =
+                matches!(command, SessionCommand::Console(ConsoleCommand::Players));
+
+            if let Err(error) = script::run(connection, command) {
+                println!("{error}");
+            }
+
+            if refresh_players {
+                completion.players = player_selectors(connection);
+            }
+        }
+    }
+}
+
+fn prompt(history: &mut FileHistory, completion: &ReplCompletion) -> dialoguer::Result<String> {
+    Input::<String>::new()
+        .with_prompt("ppc")
+        .allow_empty(true)
+        .history_with(history)
+        .completion_with(completion)
+        .interact_text()
+}
+
+/// Keeps the REPL history across sessions in the local data directory.
+struct FileHistory {
+    path: Option<PathBuf>,
+    entries: VecDeque<String>,
+}
+
+impl FileHistory {
+    fn load() -> Self {
+        let path = dirs::data_local_dir().map(|dir| dir.join("warppcs").join("ppc_history"));
+        let entries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| content.lines().rev().map(String::from).collect())
+            .unwrap_or_default();
+
+        Self { path, entries }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let lines: Vec<&str> = self.entries.iter().rev().map(String::as_str).collect();
+        let _ = fs::write(path, lines.join("\n"));
+    }
+}
+
+impl History<String> for FileHistory {
+    fn read(&self, pos: usize) -> Option<String> {
+        self.entries.get(pos).cloned()
+    }
+
+    fn write(&mut self, val: &String) {
+        if val.trim().is_empty() || self.entries.front() == Some(val) {
+            return;
+        }
+        self.entries.push_front(val.clone());
+        self.entries.truncate(MAX_HISTORY);
+        self.save();
+    }
+}
+
+/// Completes subcommands, player selectors and unit names for the last word of the input.
+struct ReplCompletion {
+    commands: Vec<String>,
+    players: Vec<String>,
+}
+
+impl ReplCompletion {
+    fn new(connection: &Connection) -> Self {
+        let mut commands = command_names::<SessionLine>();
+        commands.extend(["help", "exit"].map(String::from));
+
+        Self {
+            commands,
+            players: player_selectors(connection),
+        }
+    }
+}
+
+impl Completion for ReplCompletion {
+    fn get(&self, input: &str) -> Option<String> {
+        complete(input, &self.commands, &self.players)
+    }
+}
+
+fn player_selectors(connection: &Connection) -> Vec<String> {
+    let mut selectors = vec![String::from("local")];
+
+    let Ok(request) = request(ConsoleCommand::Players) else {
+        return selectors;
+    };
+    let Ok(response) = connection.send(request) else {
+        return selectors;
+    };
+    let Some(Ok(players)) = response
+        .get("result")
+        .map(|result| from_value::<Vec<BrpPlayerInfo>>(result.clone()))
+    else {
+        return selectors;
+    };
+
+    for player in players {
+        for selector in [player.id.to_string(), player.color] {
+            if !selectors.contains(&selector) {
+                selectors.push(selector);
+            }
+        }
+    }
+    selectors
+}
@@ -0,0 +1,131 @@
+use clap::{Parser, Subcommand, ValueHint};
+use std::{fs, path::Path, path::PathBuf, thread, time::Duration};
+
+use console_protocol::ConsoleCommand;
+
+use crate::{connection::Connection, execute};
+
+/// A single statement of a script or a REPL line.
+#[derive(Parser)]
+#[command(no_binary_name = true, disable_version_flag = true)]
+pub struct SessionLine {
+    #[command(subcommand)]
+    pub command: SessionCommand,
+}
+
+#[derive(Subcommand)]
+pub enum SessionCommand {
+    /// Pause before the next command, e.g. `wait 5s` or `wait 500ms`.
+    Wait {
+        #[arg(value_parser = parse_duration)]
+        duration: Duration,
+    },
+    /// Execute a script of commands, separated by newlines or `;`.
+    Run {
+        #[arg(value_hint = ValueHint::FilePath)]
+        script: PathBuf,
+    },
+    #[command(flatten)]
+    Console(ConsoleCommand),
+}
+
+/// Splits a script into its statements, skipping empty ones and `#` comments.
+///
+/// Yields the line number of each statement together with the statement.
+pub fn statements(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    source.lines().enumerate().flat_map(|(index, line)| {
+        let code = line.split('#').next().unwrap_or_default();
+        code.split(';')
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty())
+            .map(move |statement| (index + 1, statement))
+    })
+}
+
+pub fn parse(statement: &str) -> Result<SessionCommand, clap::Error> {
+    SessionLine::try_parse_from(statement.split_whitespace()).map(|line| line.command)
+}
+
+pub fn run(connection: &Connection, command: SessionCommand) -> Result<(), String> {
+    run_nested(connection, command, &mut Vec::new())
+}
+
+/// Parses the whole script before executing it, so a typo on the last line does not leave
+/// a half set up scenario behind.
+pub fn run_file(connection: &Connection, path: &Path) -> Result<(), String> {
+    run_file_nested(connection, path, &mut Vec::new())
+}
+
+/// Runs `command` inside the scripts in `running`, the canonical paths of the scripts
+/// currently executing, outermost first.
+fn run_nested(
+    connection: &Connection,
+    command: SessionCommand,
+    running: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    match command {
+        SessionCommand::Wait { duration } => {
+            println!("Waiting {duration:?}...");
+            thread::sleep(duration);
+        }
+        SessionCommand::Run { script } => run_file_nested(connection, &script, running)?,
+        SessionCommand::Console(command) => execute(connection, command),
+    }
+    Ok(())
+}
+
+/// A script running itself, directly or through other scripts, is rejected instead of
+/// recursing forever.
+fn run_file_nested(
+    connection: &Connection,
+    path: &Path,
+    running: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    let canonical = fs::canonicalize(path)
+        .map_err(|error| format!("Unable to read script {}: {error}", path.display()))?;
+    if running.contains(&canonical) {
+        return Err(format!(
+            "Script {} is already running, scripts cannot run themselves",
+            path.display()
+        ));
+    }
+
+    let source = fs::read_to_string(path)
+        .map_err(|error| format!("Unable to read script {}: {error}", path.display()))?;
+
+    let commands = statements(&source)
+        .map(|(line, statement)| {
+            parse(statement)
+                .map(|command| (statement, command))
+                .map_err(|error| format!("{}:{line}: `{statement}`\n{error}", path.display()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    running.push(canonical);
+    let result = commands.into_iter().try_for_each(|(statement, command)| {
+        println!("> {statement}");
+        run_nested(connection, command, running)
+    });
+    running.pop();
+    result
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (amount, unit) = match value.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(index) => value.split_at(index),
+        None => (value, "s"),
+    };
+
+    let amount: f64 = amount
+        .parse()
+        .map_err(|_| format!("invalid duration `{value}`"))?;
+
+    let seconds = match unit {
+        "ms" => amount / 1000.,
+        "s" => amount,
+        "m" => amount * 60.,
+        other => return Err(format!("unknown duration unit `{other}`, use ms, s or m")),
+    };
+
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("invalid duration `{value}`"))
+}
@@ -0,0 +1,66 @@
+use bevy_remote::BrpRequest;
+use console_protocol::{BRP_WATCH_EVENTS, BrpGameEvent, BrpWatchEvents};
+use serde_json::{Value, from_value, to_value};
+use std::{ops::ControlFlow, time::Instant};
+
+use crate::connection::Connection;
+
+pub fn run(connection: &Connection, filter: BrpWatchEvents) {
+    let request = BrpRequest {
+        jsonrpc: String::from("2.0"),
+        method: BRP_WATCH_EVENTS.into(),
+        id: None,
+        params: Some(
+            to_value(filter).expect("Unable to convert query parameters to a valid JSON value"),
+        ),
+    };
+
+    println!(
+        "Watching events on {}, press Ctrl+C to stop.",
+        connection.url()
+    );
+    let started = Instant::now();
+
+    let result = connection.stream(request, |response| {
+        if let Some(error) = response.get("error") {
+            println!("{error:#}");
+            return ControlFlow::Break(());
+        }
+
+        let events = response
+            .get("result")
+            .cloned()
+            .map(from_value::<Vec<BrpGameEvent>>);
+
+        match events {
+            Some(Ok(events)) => {
+                let elapsed = started.elapsed().as_secs_f32();
+                for event in events {
+                    print_event(elapsed, &event);
+                }
+            }
+            _ => println!("{response:#}"),
+        }
+        ControlFlow::Continue(())
+    });
+
+    if result.is_err() {
+        println!("No running bevy application found at {}.", connection.url());
+    }
+}
+
+fn print_event(elapsed: f32, event: &BrpGameEvent) {
+    let scene = match event.scene {
+        Some(scene) => format!("scene {scene}"),
+        None => String::from("-"),
+    };
+    let players = match event.players.as_slice() {
+        [] => String::from("-"),
+        ids => Value::from(ids).to_string(),
+    };
+
+    println!(
+        "[{elapsed:>8.2}s] {scene:<9} player {players:<6} {}",
+        event.kind
+    );
+}
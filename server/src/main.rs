@@ -1,11 +1,15 @@
 use bevy::prelude::*;
 
 use bevy::app::ScheduleRunnerPlugin;
-use shared::{SharedPlugin, server::networking::ServerNetworkPlugin};
+use shared::{
+    SharedPlugin,
+    server::{networking::ServerNetworkPlugin, physics::projectile::ProjectileSettings},
+};
 
-use std::time::Duration;
+use std::{env, time::Duration};
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
     let mut app = App::new();
     // app.add_plugins(SteamworksPlugin::init_app(1513980).unwrap());
 
@@ -18,6 +22,13 @@ fn main() {
 
     app.add_plugins(ServerNetworkPlugin);
 
+    if args.contains(&String::from("friendly-fire")) {
+        app.insert_resource(ProjectileSettings {
+            friendly_fire: true,
+            ..default()
+        });
+    }
+
     // app.add_systems(Startup, create_steam_server);
 
     app.run();
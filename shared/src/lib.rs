@@ -13,7 +13,7 @@ use map::{
     buildings::{BuildStatus, Building, RecruitBuilding, RespawnZone},
 };
 use networking::{Inventory, Mounted};
-use player_attacks::PlayerAttacks;
+use player_attacks::{KingAttack, PlayerAttacks};
 use player_movement::PlayerMovement;
 use serde::{Deserialize, Serialize};
 use server::{
@@ -23,12 +23,14 @@ use server::{
         },
         recruiting::{Flag, FlagAssignment, FlagHolder},
         siege_camp::SiegeCamp,
+        siege_engine::SiegeEngine,
     },
+    console::in_game::{ConsoleOutput, ConsoleRequest},
     entities::{
         Unit,
         commander::{
             ArmyFlagAssignments, ArmyPosition, CommanderAssignmentRequest,
-            CommanderCampInteraction, CommanderInteraction,
+            CommanderCampInteraction, CommanderInteraction, CommanderOrder,
         },
     },
     physics::{
@@ -40,16 +42,21 @@ use server::{
         chest::Chest,
         interaction::{InteractPlugin, Interactable, InteractableSound},
         items::Item,
+        loot::GoldPile,
         mount::Mount,
+        team::{Team, TeamPlugin},
     },
+    stats::MatchSummary,
 };
 
 use crate::{
     player_port::{PlayerPort, Portal},
     server::{
+        ai::{InAmbush, debug::AIDebugState, morale::MoraleState},
         entities::{
             commander::{ArmyFormation, CommanderAssignmentReject, CommanderPickFlag},
-            health::{Health, PlayerDefeated},
+            health::{Health, HealthFraction, PlayerDefeated},
+            status::ActiveStatuses,
         },
         physics::army_slot::ArmySlot,
         players::{chest::ChestOpened, flag::FlagDestroyed},
@@ -79,12 +86,14 @@ impl Plugin for SharedPlugin {
             PlayerAttacks,
             PlayerPort,
             InteractPlugin,
+            TeamPlugin,
         ))
         .init_resource::<ClientPlayerMap>()
         .replicate::<Moving>()
         .replicate::<Grounded>()
         .replicate::<BoxCollider>()
         .replicate::<Owner>()
+        .replicate::<Team>()
         .replicate::<Mounted>()
         .replicate::<ItemAssignment>()
         .replicate::<Interactable>()
@@ -94,11 +103,17 @@ impl Plugin for SharedPlugin {
         .replicate::<FlagHolder>()
         .replicate::<FlagDestroyed>()
         .replicate::<ChestOpened>()
+        .replicate::<InAmbush>()
+        .replicate::<MoraleState>()
+        .replicate::<ActiveStatuses>()
+        .replicate::<HealthFraction>()
+        .replicate::<AIDebugState>()
         .replicate_bundle::<(Player, Transform, Inventory)>()
         .replicate_bundle::<(RecruitBuilding, Transform)>()
         .replicate_bundle::<(Building, BuildStatus, Transform)>()
         .replicate_bundle::<(RespawnZone, Transform)>()
         .replicate_bundle::<(SiegeCamp, Transform)>()
+        .replicate_bundle::<(SiegeEngine, Transform)>()
         .replicate_bundle::<(Flag, Transform)>()
         .replicate_bundle::<(ProjectileType, Transform)>()
         .replicate_bundle::<(Unit, Transform)>()
@@ -106,6 +121,7 @@ impl Plugin for SharedPlugin {
         .replicate_bundle::<(Mount, Transform)>()
         .replicate_bundle::<(Chest, Transform)>()
         .replicate_bundle::<(Item, Transform)>()
+        .replicate_bundle::<(GoldPile, Transform)>()
         .replicate_bundle::<(ArmySlot, Transform)>()
         .sync_related_entities::<FlagAssignment>()
         .add_client_event::<ArmyPosition>(Channel::Ordered)
@@ -114,16 +130,20 @@ impl Plugin for SharedPlugin {
         .add_client_event::<StartBuild>(Channel::Ordered)
         .add_client_event::<CommanderAssignmentRequest>(Channel::Ordered)
         .add_client_event::<CommanderPickFlag>(Channel::Ordered)
+        .add_client_event::<CommanderOrder>(Channel::Ordered)
         .add_client_event::<ClientReady>(Channel::Ordered)
+        .add_client_event::<ConsoleRequest>(Channel::Ordered)
         .add_server_event::<InteractableSound>(Channel::Ordered)
         .add_server_event::<CommanderAssignmentReject>(Channel::Ordered)
         .add_server_event::<CloseBuildingDialog>(Channel::Ordered)
         .add_server_event::<GameStarted>(Channel::Ordered)
+        .add_server_event::<MatchSummary>(Channel::Ordered)
         .add_mapped_server_event::<PlayerDefeated>(Channel::Ordered)
         .add_mapped_server_event::<CommanderInteraction>(Channel::Ordered)
         .add_mapped_server_event::<OpenBuildingDialog>(Channel::Ordered)
         .add_mapped_server_event::<SetLocalPlayer>(Channel::Ordered)
         .add_mapped_server_message::<AnimationChangeEvent>(Channel::Ordered)
+        .add_server_message::<ConsoleOutput>(Channel::Ordered)
         .add_observer(spawn_clients)
         .add_observer(update_visibility)
         .add_observer(hide_on_remove)
@@ -139,6 +159,32 @@ pub struct PendingPlayers(HashMap<Entity, u64>);
 pub enum Hitby {
     Arrow,
     Melee,
+    /// Bombs and boulders bursting on impact.
+    Explosion,
+    /// Damage over time from a status effect like burning or bleeding.
+    Status,
+}
+
+impl Hitby {
+    /// How well physical armor holds up against this kind of hit, arrows glance off plate.
+    pub fn armor_effectiveness(&self) -> f32 {
+        match self {
+            Hitby::Arrow => 1.5,
+            Hitby::Melee => 1.,
+            Hitby::Explosion => 0.5,
+            Hitby::Status => 0.,
+        }
+    }
+
+    /// Chance of a shield facing the attacker to block this kind of hit outright.
+    pub fn block_chance(&self) -> f32 {
+        match self {
+            Hitby::Arrow => 0.5,
+            Hitby::Melee => 0.2,
+            Hitby::Explosion => 0.,
+            Hitby::Status => 0.,
+        }
+    }
 }
 /// Key is NetworkEntity
 /// Value is PlayerEntity
@@ -168,6 +214,8 @@ pub enum AnimationChange {
     Idle,
     Attack,
     Hit(Hitby),
+    /// A shield caught the hit.
+    Block,
     Death,
     KnockOut,
     Mount,
@@ -197,9 +245,13 @@ impl GameSceneId {
         Self(id)
     }
 
-    pub(crate) fn lobby() -> Self {
+    pub fn lobby() -> Self {
         Self(0)
     }
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -247,6 +299,7 @@ fn spawn_clients(
     mut commands: Commands,
     mut pending_players: ResMut<PendingPlayers>,
     disconnected_players: Query<(Entity, &Player), With<Disconnected>>,
+    players: Query<&Player>,
     mut game_state: ResMut<NextState<GameState>>,
 ) {
     let client_id = ClientId::Client(trigger.entity);
@@ -291,12 +344,12 @@ fn spawn_clients(
     }
 
     // No disconnected player found, spawn a new one
-    let color = fastrand::choice(PlayerColor::all_variants()).unwrap();
+    let color = PlayerColor::unused(players.iter().map(|player| player.color));
     let player = commands.spawn_empty().id();
     commands.entity(player).insert((
         Player {
             id: new_player_id,
-            color: *color,
+            color,
         },
         Transform::from_xyz(250.0, 0.0, Layers::Player.as_f32()),
         Owner::Player(player),
@@ -367,12 +420,13 @@ fn update_visibility(
             .collect();
 
         for (player_entity, _player_scene_id) in players_query.iter() {
-            let client_entity = match client_player_map.get_network_entity(&player_entity)? {
-                ClientId::Client(entity) => *entity,
-                _ => continue,
+            let Ok(ClientId::Client(client_entity)) =
+                client_player_map.get_network_entity(&player_entity)
+            else {
+                continue;
             };
 
-            if let Ok(mut visibility) = visibility_query.get_mut(client_entity) {
+            if let Ok(mut visibility) = visibility_query.get_mut(*client_entity) {
                 if player_entity.eq(&entity) {
                     let player_scene_id = player_scenes
                         .get(&entity)
@@ -390,11 +444,12 @@ fn update_visibility(
         }
     } else {
         for (player_entity, player_scene_id) in players_query.iter() {
-            let client_entity = match client_player_map.get_network_entity(&player_entity)? {
-                ClientId::Client(entity) => *entity,
-                _ => continue,
+            let Ok(ClientId::Client(client_entity)) =
+                client_player_map.get_network_entity(&player_entity)
+            else {
+                continue;
             };
-            if let Ok(mut visibility) = visibility_query.get_mut(client_entity) {
+            if let Ok(mut visibility) = visibility_query.get_mut(*client_entity) {
                 visibility.set_visibility(entity, player_scene_id.eq(new_entity_scene_id));
             }
         }
@@ -444,6 +499,7 @@ impl MapEntities for SetLocalPlayer {
     Sprite,
     Anchor::BOTTOM_CENTER,
     Inventory,
+    KingAttack,
 )]
 pub struct Player {
     pub id: u64,
@@ -474,6 +530,23 @@ pub enum PlayerColor {
     Gray,
 }
 
+impl PlayerColor {
+    /// A random color nobody has taken yet, any color once all of them are.
+    pub fn unused(taken: impl IntoIterator<Item = PlayerColor>) -> PlayerColor {
+        let taken: Vec<PlayerColor> = taken.into_iter().collect();
+        let free: Vec<PlayerColor> = PlayerColor::all_variants()
+            .iter()
+            .copied()
+            .filter(|color| !taken.contains(color))
+            .collect();
+
+        fastrand::choice(&free)
+            .or_else(|| fastrand::choice(PlayerColor::all_variants()))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Component, Copy, Clone, Default, Deserialize, Serialize)]
 pub struct BoxCollider {
     pub dimension: Vec2,
@@ -533,6 +606,7 @@ pub fn flag_collider() -> BoxCollider {
 }
 
 #[derive(Debug, Component, Eq, PartialEq, Serialize, Deserialize, Copy, Clone)]
+#[component(map_entities)]
 pub enum Owner {
     Player(Entity),
     Bandits,
@@ -545,18 +619,6 @@ impl Owner {
             Owner::Bandits => Err(BevyError::from("Owner is not a player")),
         }
     }
-
-    pub fn is_different_faction(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Owner::Player { 0: id1 }, Owner::Player { 0: id2 }) => id1 != id2,
-            (Owner::Player { .. }, Owner::Bandits) | (Owner::Bandits, Owner::Player { .. }) => true,
-            (Owner::Bandits, Owner::Bandits) => false,
-        }
-    }
-
-    pub fn is_same_faction(&self, other: &Self) -> bool {
-        !self.is_different_faction(other)
-    }
 }
 
 impl MapEntities for Owner {
@@ -565,7 +627,7 @@ impl MapEntities for Owner {
             Owner::Player(entity) => {
                 *entity = entity_mapper.get_mapped(*entity);
             }
-            Owner::Bandits => todo!(),
+            Owner::Bandits => {}
         }
     }
 }
@@ -20,6 +20,20 @@ impl Plugin for NetworkRegistry {
 #[derive(Debug, Deserialize, Message, Serialize)]
 pub enum LobbyMessage {
     StartGame,
+    /// Adds a computer controlled king that takes a player slot.
+    AddRival(Difficulty),
+    /// Removes the last added computer controlled king.
+    RemoveRival,
+    /// Moves the sending player to the next team.
+    CycleTeam,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Mappable, PartialEq, Eq)]
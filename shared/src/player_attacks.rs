@@ -1,15 +1,24 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 use bevy_replicon::prelude::*;
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    AnimationChange, AnimationChangeEvent, ClientPlayerMap, ClientPlayerMapExt,
-    networking::WorldDirection,
+    AnimationChange, AnimationChangeEvent, ClientPlayerMap, ClientPlayerMapExt, GameSceneId, Hitby,
+    Owner,
+    map::buildings::{BuildStatus, Building, BuildingType, MainBuildingLevels},
+    networking::{Mounted, WorldDirection},
     server::{
         ai::UnitBehaviour,
         buildings::recruiting::{FlagHolder, FlagUnits},
-        entities::commander::ArmyFlagAssignments,
+        entities::{
+            commander::ArmyFlagAssignments,
+            health::{DamageType, DelayedDamage, Health, TakeDamage},
+        },
+        physics::spatial::SpatialIndex,
+        players::team::Factions,
     },
 };
 
@@ -18,40 +27,130 @@ pub struct AttackIndicator {
     pub direction: WorldDirection,
 }
 
+/// How far units holding a position engage enemies.
+const HOLD_POSITION_LEASH: f32 = 80.;
+
+/// How far units defending a building chase enemies before returning to it.
+const DEFEND_BUILDING_LEASH: f32 = 150.;
+
+/// Only buildings this close to the player can be picked for [`ArmyOrder::DefendBuilding`].
+const DEFEND_BUILDING_DISTANCE: f32 = 300.;
+
+/// Seconds between the king's swing and the hit landing.
+const KING_HIT_DELAY: f32 = 0.3;
+
+/// The king's own melee attack, used while no flag is held.
+#[derive(Component)]
+pub struct KingAttack {
+    pub damage: f32,
+    pub range: f32,
+    pub cooldown: Timer,
+}
+
+impl Default for KingAttack {
+    fn default() -> Self {
+        let mut cooldown = Timer::from_seconds(0.8, TimerMode::Once);
+        cooldown.tick(Duration::MAX);
+        Self {
+            damage: 15.,
+            range: 25.,
+            cooldown,
+        }
+    }
+}
+
+impl KingAttack {
+    /// The king hits harder the further the main building is upgraded.
+    fn damage(&self, main_building: Option<MainBuildingLevels>) -> f32 {
+        let multiplier = match main_building {
+            None | Some(MainBuildingLevels::Tent) => 1.,
+            Some(MainBuildingLevels::Hall) => 1.5,
+            Some(MainBuildingLevels::Castle) => 2.,
+        };
+        self.damage * multiplier
+    }
+}
+
 pub struct PlayerAttacks;
 
 impl Plugin for PlayerAttacks {
     fn build(&self, app: &mut App) {
         app.add_client_event::<Attack>(Channel::Ordered)
+            .add_client_event::<ArmyOrder>(Channel::Ordered)
             .add_observer(attack)
-            .add_systems(Update, attack_input.before(ClientSystems::Send));
+            .add_observer(army_order)
+            .add_systems(Update, attack_input.before(ClientSystems::Send))
+            .add_systems(
+                FixedUpdate,
+                king_attack_cooldown.run_if(in_state(ClientState::Disconnected)),
+            );
     }
 }
 
 #[derive(Deserialize, Serialize, Event)]
 struct Attack(usize);
 
+/// Order for the units of the held flag and, if it belongs to a commander, the whole army.
+#[derive(Deserialize, Serialize, Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArmyOrder {
+    FollowFlag,
+    HoldPosition,
+    /// Defends the nearest built building of the player.
+    DefendBuilding,
+    Charge,
+}
+
 fn attack_input(keyboard_input: Res<ButtonInput<KeyCode>>, mut commands: Commands) -> Result {
     if keyboard_input.just_pressed(KeyCode::KeyE) {
         commands.client_trigger(Attack(0));
     }
+    if keyboard_input.just_pressed(KeyCode::KeyR) {
+        commands.client_trigger(ArmyOrder::FollowFlag);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+        commands.client_trigger(ArmyOrder::HoldPosition);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyG) {
+        commands.client_trigger(ArmyOrder::DefendBuilding);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        commands.client_trigger(ArmyOrder::Charge);
+    }
     Ok(())
 }
 
 fn attack(
     trigger: On<FromClient<Attack>>,
     mut animation: MessageWriter<ToClients<AnimationChangeEvent>>,
-    flag_holder: Query<(Option<&FlagHolder>, &Transform)>,
+    mut flag_holder: Query<(
+        Option<&FlagHolder>,
+        &Transform,
+        &GameSceneId,
+        &mut KingAttack,
+        Has<Mounted>,
+    )>,
+    targets: Query<(&Transform, &Owner), With<Health>>,
+    buildings: Query<(&Building, &Owner)>,
     units: Query<&FlagUnits>,
     army: Query<&ArmyFlagAssignments>,
     behaviour: Query<&UnitBehaviour>,
+    transforms: Query<&Transform>,
+    factions: Factions,
+    index: Res<SpatialIndex>,
     client_player_map: Res<ClientPlayerMap>,
     mut commands: Commands,
 ) -> Result {
     let player = client_player_map.get_player(&trigger.client_id)?;
-    let (maybe_flag_holder, transform) = flag_holder.get(*player)?;
+    let (maybe_flag_holder, transform, game_scene_id, mut king_attack, mounted) =
+        flag_holder.get_mut(*player)?;
+    let owner = Owner::Player(*player);
 
     let Some(flag_holder) = maybe_flag_holder else {
+        if mounted || !king_attack.cooldown.is_finished() {
+            return Ok(());
+        }
+        king_attack.cooldown.reset();
+
         animation.write(ToClients {
             mode: SendMode::Broadcast,
             message: AnimationChangeEvent {
@@ -59,6 +158,43 @@ fn attack(
                 change: AnimationChange::Attack,
             },
         });
+
+        let main_building = buildings
+            .iter()
+            .find_map(|(building, building_owner)| match building.building_type {
+                BuildingType::MainBuilding { level } if *building_owner == owner => Some(level),
+                _ => None,
+            });
+        let damage = king_attack.damage(main_building);
+
+        // Hits every enemy in reach on the side the king is facing.
+        let x = transform.translation.x;
+        let facing = transform.scale.x.signum();
+        for target in index.near(*game_scene_id, x, king_attack.range) {
+            let Ok((target_transform, target_owner)) = targets.get(target) else {
+                continue;
+            };
+            if target == *player || factions.is_allied(target_owner, &owner) {
+                continue;
+            }
+
+            let delta_x = target_transform.translation.x - x;
+            if delta_x.abs() > king_attack.range || delta_x * facing < 0. {
+                continue;
+            }
+
+            commands.spawn(DelayedDamage::after(
+                KING_HIT_DELAY,
+                TakeDamage {
+                    target_entity: target,
+                    damage,
+                    damage_type: DamageType::Physical,
+                    direction: delta_x.into(),
+                    by: Hitby::Melee,
+                    attacker: Some(*player),
+                },
+            ));
+        }
         return Ok(());
     };
 
@@ -72,21 +208,155 @@ fn attack(
 
     let behaviour = behaviour.get(unit)?;
     let new_behaviour = match behaviour {
-        UnitBehaviour::Attack(_) => UnitBehaviour::FollowFlag,
         UnitBehaviour::FollowFlag | UnitBehaviour::Idle => {
             UnitBehaviour::Attack(transform.scale.x.into())
         }
+        // Any other order is cancelled.
+        UnitBehaviour::Attack(_)
+        | UnitBehaviour::HoldPosition { .. }
+        | UnitBehaviour::DefendBuilding { .. }
+        | UnitBehaviour::Charge(_) => UnitBehaviour::FollowFlag,
+    };
+
+    give_order(
+        flag,
+        unit,
+        new_behaviour,
+        &units,
+        &army,
+        &transforms,
+        &mut commands,
+    )
+}
+
+/// Buildings an army can be ordered to defend.
+pub type OrderBuildings<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static Owner,
+        &'static GameSceneId,
+        &'static BuildStatus,
+    ),
+    With<Building>,
+>;
+
+fn army_order(
+    trigger: On<FromClient<ArmyOrder>>,
+    flag_holder: Query<(Option<&FlagHolder>, &GameSceneId)>,
+    units: Query<&FlagUnits>,
+    army: Query<&ArmyFlagAssignments>,
+    buildings: OrderBuildings,
+    transforms: Query<&Transform>,
+    factions: Factions,
+    client_player_map: Res<ClientPlayerMap>,
+    mut commands: Commands,
+) -> Result {
+    let player = client_player_map.get_player(&trigger.client_id)?;
+    let (maybe_flag_holder, game_scene_id) = flag_holder.get(*player)?;
+
+    let Some(flag_holder) = maybe_flag_holder else {
+        return Ok(());
+    };
+    let flag = **flag_holder;
+    let Some(unit) = units.get(flag)?.iter().next() else {
+        return Ok(());
+    };
+
+    let Some(new_behaviour) = order_behaviour(
+        **trigger.event(),
+        *player,
+        transforms.get(*player)?,
+        game_scene_id,
+        &buildings,
+        &factions,
+    ) else {
+        return Ok(());
+    };
+
+    give_order(
+        flag,
+        unit,
+        new_behaviour,
+        &units,
+        &army,
+        &transforms,
+        &mut commands,
+    )
+}
+
+/// The behaviour `order` gives to the army of `player`, or `None` if it can't be carried out.
+pub fn order_behaviour(
+    order: ArmyOrder,
+    player: Entity,
+    transform: &Transform,
+    game_scene_id: &GameSceneId,
+    buildings: &OrderBuildings,
+    factions: &Factions,
+) -> Option<UnitBehaviour> {
+    let x = transform.translation.x;
+    let new_behaviour = match order {
+        ArmyOrder::FollowFlag => UnitBehaviour::FollowFlag,
+        ArmyOrder::HoldPosition => UnitBehaviour::HoldPosition {
+            x,
+            leash: HOLD_POSITION_LEASH,
+        },
+        ArmyOrder::DefendBuilding => {
+            let nearest = buildings
+                .iter()
+                .filter(|(_, _, owner, building_scene_id, status)| {
+                    factions.is_allied(owner, &Owner::Player(player))
+                        && *building_scene_id == game_scene_id
+                        && matches!(status, BuildStatus::Built { .. })
+                })
+                .map(|(building, building_transform, ..)| {
+                    (building, (building_transform.translation.x - x).abs())
+                })
+                .filter(|(_, distance)| *distance <= DEFEND_BUILDING_DISTANCE)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+            let (building, _) = nearest?;
+            UnitBehaviour::DefendBuilding {
+                building,
+                leash: DEFEND_BUILDING_LEASH,
+            }
+        }
+        ArmyOrder::Charge => UnitBehaviour::Charge(transform.scale.x.into()),
     };
+    Some(new_behaviour)
+}
 
+fn king_attack_cooldown(mut kings: Query<&mut KingAttack>, time: Res<Time>) {
+    for mut king_attack in kings.iter_mut() {
+        king_attack.cooldown.tick(time.delta());
+    }
+}
+
+/// Gives `new_behaviour` to the units of `flag` and, if `unit` commands an army, to all of its units.
+pub fn give_order(
+    flag: Entity,
+    unit: Entity,
+    new_behaviour: UnitBehaviour,
+    units: &Query<&FlagUnits>,
+    army: &Query<&ArmyFlagAssignments>,
+    transforms: &Query<&Transform>,
+    commands: &mut Commands,
+) -> Result {
     match new_behaviour {
-        UnitBehaviour::Attack(direction) => {
+        UnitBehaviour::Attack(direction) | UnitBehaviour::Charge(direction) => {
             commands.entity(flag).insert(AttackIndicator { direction });
         }
-        UnitBehaviour::FollowFlag | UnitBehaviour::Idle => {
+        UnitBehaviour::FollowFlag
+        | UnitBehaviour::Idle
+        | UnitBehaviour::HoldPosition { .. }
+        | UnitBehaviour::DefendBuilding { .. } => {
             commands.entity(flag).remove::<AttackIndicator>();
         }
     }
 
+    let flag_units = units.get(flag)?;
     let mut all_units: Vec<Entity> = flag_units.iter().collect();
     if let Ok(army) = army.get(unit) {
         for formation_flag in army.flags.iter().flatten() {
@@ -97,7 +367,15 @@ fn attack(
     }
 
     for unit in all_units.iter() {
-        commands.entity(*unit).insert(new_behaviour.clone());
+        let behaviour = match new_behaviour {
+            // Every unit holds the spot it stands on instead of gathering at one.
+            UnitBehaviour::HoldPosition { leash, .. } => UnitBehaviour::HoldPosition {
+                x: transforms.get(*unit)?.translation.x,
+                leash,
+            },
+            _ => new_behaviour.clone(),
+        };
+        commands.entity(*unit).insert(behaviour);
     }
     Ok(())
 }
@@ -2,22 +2,26 @@ use bevy::prelude::*;
 
 use bevy_behave::prelude::BehaveCtx;
 use bevy_replicon::prelude::{SendMode, ToClients};
-use std::f32::consts::FRAC_PI_4;
 
 use super::{Attack, Target};
 use crate::{
-    AnimationChange, AnimationChangeEvent, GRAVITY_G, GameSceneId, Hitby, Owner,
+    AnimationChange, AnimationChangeEvent, GameSceneId, Hitby, Owner,
     map::Layers,
     networking::{UnitType, WorldDirection},
     server::{
         ai::WaitToAttack,
         buildings::recruiting::FlagAssignment,
         entities::{
-            Damage, Unit,
+            Accuracy, Ammunition, Damage, ProjectileRange, Unit,
             commander::ArmyFlagAssignments,
-            health::{DelayedDamage, Health, TakeDamage},
+            health::{DamageType, DelayedDamage, Health, TakeDamage},
+            status::StatusEffects,
+        },
+        physics::{
+            ballistics::{Ballistics, lead_target, scatter},
+            movement::Velocity,
+            projectile::{ProjectileHits, ProjectileType, Shooter},
         },
-        physics::{movement::Velocity, projectile::ProjectileType},
     },
 };
 
@@ -74,6 +78,10 @@ fn wait_to_attack(
     Ok(())
 }
 
+/// Height above a target's feet projectiles are aimed at.
+const TARGET_HEIGHT: f32 = 14.;
+
+#[allow(clippy::type_complexity)]
 fn process_attacks(
     query: Query<(&BehaveCtx, &Attack)>,
     mut commands: Commands,
@@ -84,13 +92,29 @@ fn process_attacks(
         &Transform,
         &Damage,
         &GameSceneId,
+        Option<&ProjectileRange>,
+        Option<&Accuracy>,
+        Option<&Ammunition>,
+        &StatusEffects,
     )>,
     mut animation: MessageWriter<ToClients<AnimationChangeEvent>>,
-    position: Query<&Transform>,
+    position: Query<(&Transform, Option<&Velocity>)>,
+    ballistics: Res<Ballistics>,
 ) {
     for (ctx, attacking_range) in query.iter() {
         let entity = ctx.target_entity();
-        let Ok((mut unit, target, owner, transform, damage, game_scene_id)) = unit.get_mut(entity)
+        let Ok((
+            mut unit,
+            target,
+            owner,
+            transform,
+            damage,
+            game_scene_id,
+            projectile_range,
+            accuracy,
+            ammunition,
+            effects,
+        )) = unit.get_mut(entity)
         else {
             commands.trigger(ctx.failure());
             continue;
@@ -100,13 +124,18 @@ fn process_attacks(
             continue;
         }
 
-        let target_pos = if let Ok(target_transform) = position.get(**target) {
-            target_transform.translation
-        } else {
-            commands.trigger(ctx.failure());
-            continue;
-        };
+        let (target_pos, target_velocity) =
+            if let Ok((target_transform, target_velocity)) = position.get(**target) {
+                (
+                    target_transform.translation,
+                    target_velocity.map(|velocity| velocity.0.x).unwrap_or(0.),
+                )
+            } else {
+                commands.trigger(ctx.failure());
+                continue;
+            };
         let delta_x = target_pos.x - transform.translation.x;
+        let damage = **damage * effects.damage_multiplier();
 
         match attacking_range {
             Attack::Melee => {
@@ -114,9 +143,11 @@ fn process_attacks(
                     &unit.unit_type,
                     TakeDamage {
                         target_entity: **target,
-                        damage: **damage,
+                        damage,
+                        damage_type: DamageType::Physical,
                         direction: delta_x.into(),
                         by: Hitby::Melee,
+                        attacker: Some(entity),
                     },
                 ));
             }
@@ -126,38 +157,37 @@ fn process_attacks(
                     transform.translation.y + 5.,
                     Layers::Projectile.as_f32(),
                 );
-                let projectile_type = ProjectileType::Arrow;
-                let target_pos = target_pos.with_y(14.);
-
-                let delta_y = target_pos.y - transform.translation.y;
-
-                let theta = if delta_x > 0. { FRAC_PI_4 } else { 2.3561944 };
-
-                let cos_theta = theta.cos();
-                let sin_theta = theta.sin();
-                let tan_theta = sin_theta / cos_theta;
-                let cos_theta_squared = cos_theta * cos_theta;
-
-                let denominator = 2.0 * (delta_x * tan_theta - delta_y) * cos_theta_squared;
-                if denominator <= 0.0 {
-                    warn!(
-                        "Shooting not possible, theta: {}, delta_x: {}",
-                        theta, delta_x
-                    );
+                let projectile_type = ammunition
+                    .map(|ammunition| **ammunition)
+                    .unwrap_or(ProjectileType::Arrow);
+                let target_pos = target_pos.truncate() + Vec2::Y * TARGET_HEIGHT;
+
+                let range = projectile_range
+                    .map(|range| **range)
+                    .unwrap_or(delta_x.abs());
+                let distance = arrow_position.truncate().distance(target_pos);
+                let speed = ballistics.launch_speed(range);
+                let arc = ballistics.arc(distance, range);
+
+                let Some(launch) = lead_target(
+                    arrow_position.truncate(),
+                    target_pos,
+                    target_velocity,
+                    speed,
+                    arc,
+                ) else {
+                    commands.trigger(ctx.failure());
                     continue;
-                }
-
-                let numerator = GRAVITY_G * delta_x * delta_x;
-
-                let v0_squared = numerator / denominator;
-                let speed = v0_squared.sqrt();
+                };
 
-                let velocity = Velocity(Vec2::from_angle(theta) * speed);
+                let accuracy = accuracy.map(|accuracy| **accuracy).unwrap_or(0.);
+                let launch = scatter(launch, ballistics.spread(distance, range, accuracy));
+                let velocity = Velocity(launch);
 
                 let arrow_transform = Transform {
                     translation: arrow_position,
                     scale: Vec3::splat(1.0),
-                    rotation: Quat::from_rotation_z(theta),
+                    rotation: Quat::from_rotation_z(launch.to_angle()),
                 };
 
                 commands.spawn((
@@ -165,7 +195,9 @@ fn process_attacks(
                     *owner,
                     projectile_type,
                     velocity,
-                    Damage(**damage),
+                    Damage(damage),
+                    Shooter(entity),
+                    ProjectileHits::new(projectile_type.pierce()),
                     *game_scene_id,
                 ));
             }
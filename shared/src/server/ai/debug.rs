@@ -0,0 +1,87 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use bevy_behave::prelude::BehaveCtx;
+use serde::{Deserialize, Serialize};
+
+use crate::server::entities::{Unit, health::Health};
+
+use super::{BanditBehaviour, Target, UnitBehaviour};
+
+pub struct AIDebugPlugin;
+
+impl Plugin for AIDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedPostUpdate,
+            (update_ai_debug_state, remove_ai_debug_state)
+                .run_if(resource_exists::<AIDebugEnabled>),
+        );
+    }
+}
+
+/// Replicates the [`AIDebugState`] of every unit to clients.
+#[derive(Resource)]
+pub struct AIDebugEnabled;
+
+/// Compact view of what a unit's behaviour tree is doing, drawn by the client's gizmos.
+#[derive(Component, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AIDebugState {
+    /// Name of the running behaviour tree node, if any.
+    pub node: Option<String>,
+    #[entities]
+    pub target: Option<Entity>,
+    /// The unit's [`UnitBehaviour`] or [`BanditBehaviour`].
+    pub behaviour: String,
+}
+
+#[allow(clippy::type_complexity)]
+fn update_ai_debug_state(
+    mut units: Query<
+        (
+            Entity,
+            Option<&Target>,
+            Option<&UnitBehaviour>,
+            Option<&BanditBehaviour>,
+            Option<&mut AIDebugState>,
+        ),
+        (With<Unit>, With<Health>),
+    >,
+    nodes: Query<(&Name, &BehaveCtx)>,
+    mut commands: Commands,
+) {
+    let running: HashMap<Entity, &str> = nodes
+        .iter()
+        .map(|(name, ctx)| (ctx.target_entity(), name.as_str()))
+        .collect();
+
+    for (entity, target, unit_behaviour, bandit_behaviour, debug_state) in units.iter_mut() {
+        let behaviour = match (unit_behaviour, bandit_behaviour) {
+            (Some(behaviour), _) => format!("{behaviour:?}"),
+            (None, Some(behaviour)) => format!("{behaviour:?}"),
+            (None, None) => String::new(),
+        };
+        let state = AIDebugState {
+            node: running.get(&entity).map(|node| node.to_string()),
+            target: target.map(|target| **target),
+            behaviour,
+        };
+
+        match debug_state {
+            Some(mut debug_state) => {
+                debug_state.set_if_neq(state);
+            }
+            None => {
+                commands.entity(entity).insert(state);
+            }
+        }
+    }
+}
+
+fn remove_ai_debug_state(
+    dead: Query<Entity, (With<AIDebugState>, Without<Health>)>,
+    mut commands: Commands,
+) {
+    for entity in dead.iter() {
+        commands.entity(entity).try_remove::<AIDebugState>();
+    }
+}
@@ -0,0 +1,375 @@
+use bevy::prelude::*;
+
+use bevy_behave::{Behave, behave, prelude::*};
+
+use crate::{
+    GameSceneId, Owner,
+    networking::{UnitType, WorldDirection},
+    server::{
+        buildings::recruiting::{FlagAssignment, FlagUnits},
+        entities::{
+            MeleeRange, ProjectileRange, Unit,
+            commander::{ArmyFlagAssignments, ArmyPosition},
+            health::Health,
+        },
+        physics::{
+            army_slot::ArmySlot,
+            attachment::AttachedTo,
+            movement::{RandomVelocityMul, Speed, Velocity},
+            spatial::SpatialIndex,
+        },
+        players::team::Factions,
+    },
+};
+
+use super::{
+    Attack, BehaveTarget, BeingPushed, DetermineTarget, TargetInMeleeRange, UnitBehaviour,
+    WalkIntoRange, movement::walk_towards,
+};
+
+/// How far the line advances ahead of its slowest unit.
+const LINE_STEP: f32 = 10.;
+
+/// How far melee units leave their position in the formation to engage an enemy.
+const FORMATION_REACH: f32 = 30.;
+
+/// Ranged units fall back once an enemy gets this close.
+const RETREAT_DISTANCE: f32 = 40.;
+
+pub struct FormationPlugin;
+
+impl Plugin for FormationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(check_safe_to_shoot)
+            .add_systems(FixedUpdate, (update_army_lines, keep_formation).chain());
+    }
+}
+
+/// Position of the front line of an attacking army, kept on its commander.
+#[derive(Component, Clone, Copy, Deref)]
+pub struct ArmyLine(f32);
+
+/// Limits target selection of units fighting in formation to enemies within this distance.
+#[derive(Component, Clone, Copy, Deref)]
+pub struct Reach(pub f32);
+
+/// Walks to the unit's place relative to the [`ArmyLine`] of its commander.
+#[derive(Component, Clone)]
+pub struct KeepFormation {
+    commander: Entity,
+    /// `None` for the commander itself, which stays behind its army.
+    position: Option<ArmyPosition>,
+    direction: WorldDirection,
+    /// Falls back from enemies instead of standing its ground.
+    keep_distance: bool,
+}
+
+/// Succeeds if an enemy is within projectile range and none is close enough to hit back.
+#[derive(Event, Clone)]
+struct SafeToShoot;
+
+/// Distance between the front line and the units of a formation position.
+fn gap(position: Option<ArmyPosition>) -> f32 {
+    match position {
+        Some(ArmyPosition::Front) => 0.,
+        Some(ArmyPosition::Middle) => 20.,
+        Some(ArmyPosition::Back) => 60.,
+        None => 80.,
+    }
+}
+
+/// Finds the commander and formation position of a unit, if it fights in a commander's army.
+pub(super) fn formation_of(
+    entity: Entity,
+    unit: &Unit,
+    flag_assignment: Option<&FlagAssignment>,
+    attached: &Query<&AttachedTo>,
+    slots: &Query<&ArmySlot>,
+    armies: &Query<&ArmyFlagAssignments>,
+) -> Option<(Entity, Option<ArmyPosition>)> {
+    if unit.unit_type == UnitType::Commander && armies.contains(entity) {
+        return Some((entity, None));
+    }
+
+    let flag = **flag_assignment?;
+    let slot = attached.get(flag).ok()?;
+    let commander = slots.get(**slot).ok()?.commander;
+    let army = armies.get(commander).ok()?;
+
+    army.flags
+        .iter_enums()
+        .find(|(_, assigned)| **assigned == Some(flag))
+        .map(|(position, _)| (commander, Some(position)))
+}
+
+/// Behaviour of a unit attacking in its commander's formation.
+///
+/// Melee units hold their place and only step out to engage enemies within [`Reach`],
+/// ranged units shoot from their place and fall back, only fighting in melee once cornered.
+pub(super) fn formation_tree(
+    entity: Entity,
+    commander: Entity,
+    position: Option<ArmyPosition>,
+    direction: WorldDirection,
+    ranged: bool,
+) -> Tree<Behave> {
+    let formation = KeepFormation {
+        commander,
+        position,
+        direction,
+        keep_distance: ranged,
+    };
+
+    if ranged {
+        return behave!(
+            Behave::Forever => {
+                Behave::Fallback => {
+                    Behave::Sequence => {
+                        Behave::trigger(DetermineTarget),
+                        Behave::trigger(SafeToShoot),
+                        Behave::spawn_named(
+                            "Attack nearest enemy Range",
+                            (
+                                Attack::Projectile,
+                                BehaveInterrupt::by_not(SafeToShoot),
+                                BehaveTarget(entity),
+                            ),
+                        )
+                    },
+                    Behave::Sequence => {
+                        Behave::trigger(DetermineTarget),
+                        Behave::trigger(TargetInMeleeRange),
+                        Behave::spawn_named(
+                            "Attack nearest enemy Melee",
+                            (
+                                Attack::Melee,
+                                BehaveInterrupt::by_not(TargetInMeleeRange).or(SafeToShoot),
+                                BehaveTarget(entity)
+                            ),
+                        ),
+                    },
+                    Behave::spawn_named(
+                        "Keeping distance from the enemy",
+                        (
+                            formation,
+                            BehaveInterrupt::by(SafeToShoot)
+                                .or(TargetInMeleeRange)
+                                .or(BeingPushed),
+                            BehaveTarget(entity)
+                        )
+                    )
+                }
+            }
+        );
+    }
+
+    let stance_name = match position {
+        Some(ArmyPosition::Front) => "Holding the line",
+        _ => "Keeping formation",
+    };
+
+    behave!(
+        Behave::Forever => {
+            Behave::Fallback => {
+                Behave::Sequence => {
+                    Behave::trigger(DetermineTarget),
+                    Behave::Fallback => {
+                        Behave::Sequence => {
+                            Behave::trigger(TargetInMeleeRange),
+                            Behave::spawn_named(
+                                "Attack nearest enemy Melee",
+                                (
+                                    Attack::Melee,
+                                    BehaveInterrupt::by_not(TargetInMeleeRange),
+                                    BehaveTarget(entity)
+                                ),
+                            ),
+                        },
+                        Behave::spawn_named(
+                            "Walking to target",
+                            (
+                                WalkIntoRange,
+                                BehaveInterrupt::by(TargetInMeleeRange),
+                                BehaveTimeout::from_secs(2.0, false),
+                                BehaveTarget(entity)
+                            )
+                        )
+                    }
+                },
+                Behave::spawn_named(
+                    stance_name,
+                    (
+                        formation,
+                        BehaveInterrupt::by(DetermineTarget).or(BeingPushed),
+                        BehaveTarget(entity)
+                    )
+                )
+            }
+        }
+    )
+}
+
+/// The [`Reach`] of a unit fighting in formation.
+pub(super) fn reach(melee_range: f32, projectile_range: Option<f32>) -> Reach {
+    match projectile_range {
+        Some(projectile_range) => Reach(projectile_range),
+        None => Reach(melee_range + FORMATION_REACH),
+    }
+}
+
+fn update_army_lines(
+    mut commanders: Query<(
+        Entity,
+        &Transform,
+        &ArmyFlagAssignments,
+        &UnitBehaviour,
+        Option<&mut ArmyLine>,
+    )>,
+    flag_units: Query<&FlagUnits>,
+    transforms: Query<&Transform, With<Unit>>,
+    mut commands: Commands,
+) {
+    for (commander, transform, army, behaviour, line) in commanders.iter_mut() {
+        let UnitBehaviour::Attack(direction) = behaviour else {
+            if line.is_some() {
+                commands.entity(commander).try_remove::<ArmyLine>();
+            }
+            continue;
+        };
+        let direction: f32 = (*direction).into();
+        let transforms = &transforms;
+
+        // Where the front line would be, judging by each unit's position.
+        let line_of = |positions: &[ArmyPosition]| {
+            positions
+                .iter()
+                .filter_map(|position| (*army.flags.get(*position)).map(|flag| (*position, flag)))
+                .filter_map(|(position, flag)| {
+                    flag_units.get(flag).ok().map(|units| (position, units))
+                })
+                .flat_map(|(position, units)| {
+                    units.iter().filter_map(move |unit| {
+                        transforms
+                            .get(unit)
+                            .ok()
+                            .map(|unit| unit.translation.x + direction * gap(Some(position)))
+                    })
+                })
+                .min_by(|a, b| (a * direction).total_cmp(&(b * direction)))
+        };
+
+        // The line advances at the pace of its slowest unit and stops where it meets the enemy.
+        let x = line_of(&[ArmyPosition::Front, ArmyPosition::Middle])
+            .or_else(|| line_of(&[ArmyPosition::Back]))
+            .unwrap_or(transform.translation.x + direction * gap(None));
+
+        match line {
+            Some(mut line) => line.0 = x,
+            None => {
+                commands.entity(commander).insert(ArmyLine(x));
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn keep_formation(
+    query: Query<(&BehaveCtx, &KeepFormation)>,
+    mut unit: Query<(
+        &mut Velocity,
+        &Transform,
+        &Owner,
+        &GameSceneId,
+        &RandomVelocityMul,
+        &Speed,
+    )>,
+    lines: Query<&ArmyLine>,
+    enemies: Query<(&Transform, &Owner), With<Health>>,
+    factions: Factions,
+    index: Res<SpatialIndex>,
+) -> Result {
+    for (ctx, formation) in query.iter() {
+        let Ok(line) = lines.get(formation.commander) else {
+            continue;
+        };
+        let (mut velocity, transform, owner, game_scene_id, rand_velocity_mul, speed) =
+            unit.get_mut(ctx.target_entity())?;
+
+        let x = transform.translation.x;
+        let speed = **speed * **rand_velocity_mul;
+        let direction: f32 = formation.direction.into();
+
+        if formation.keep_distance {
+            let threat = index
+                .near(*game_scene_id, x, RETREAT_DISTANCE)
+                .into_iter()
+                .filter_map(|candidate| enemies.get(candidate).ok())
+                .filter(|(_, other_owner)| factions.is_enemy(owner, other_owner))
+                .map(|(other_transform, _)| other_transform.translation.x)
+                .find(|other_x| (other_x - x).abs() <= RETREAT_DISTANCE);
+
+            if let Some(threat) = threat {
+                velocity.0.x = (x - threat).signum() * speed;
+                continue;
+            }
+        }
+
+        let place = **line + direction * LINE_STEP - direction * gap(formation.position);
+
+        match formation.position {
+            // The front only moves forward, it never gives up ground to regroup.
+            Some(ArmyPosition::Front) if (place - x) * direction <= 0. => velocity.0.x = 0.,
+            _ => {
+                walk_towards(&mut velocity, x, place, speed);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_safe_to_shoot(
+    trigger: On<BehaveTrigger<SafeToShoot>>,
+    query: Query<(
+        &Transform,
+        &Owner,
+        &GameSceneId,
+        &ProjectileRange,
+        &MeleeRange,
+    )>,
+    others: Query<(&Transform, &Owner), With<Health>>,
+    factions: Factions,
+    index: Res<SpatialIndex>,
+    mut commands: Commands,
+) -> Result {
+    let ctx = trigger.ctx();
+    let Ok((transform, owner, game_scene_id, projectile_range, melee_range)) =
+        query.get(ctx.target_entity())
+    else {
+        commands.trigger(ctx.failure());
+        return Ok(());
+    };
+    let x = transform.translation.x;
+
+    let nearest = index
+        .near(*game_scene_id, x, **projectile_range)
+        .into_iter()
+        .filter_map(|candidate| others.get(candidate).ok())
+        .filter(|(_, other_owner)| factions.is_enemy(owner, other_owner))
+        .map(|(other_transform, _)| {
+            transform
+                .translation
+                .truncate()
+                .distance(other_transform.translation.truncate())
+        })
+        .min_by(|a, b| a.total_cmp(b));
+
+    match nearest {
+        Some(distance)
+            if distance <= **projectile_range && distance > RETREAT_DISTANCE.max(**melee_range) =>
+        {
+            commands.trigger(ctx.success());
+        }
+        _ => commands.trigger(ctx.failure()),
+    }
+    Ok(())
+}
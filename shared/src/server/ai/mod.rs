@@ -3,21 +3,33 @@ use bevy_behave::prelude::*;
 
 use attack::AIAttackPlugin;
 use bevy_behave::{Behave, behave};
-use movement::{AIMovementPlugin, FollowFlag, Roam};
+use debug::AIDebugPlugin;
+use formation::{FormationPlugin, Reach, formation_of, formation_tree, reach};
+use morale::{MoralePlugin, MoraleState};
+use movement::{AIMovementPlugin, FleeToEdge, FollowFlag, GuardPost, LieInWait, Patrol, Roam};
+use serde::{Deserialize, Serialize};
+use targeting::{RecentDamage, TargetCandidate, TargetKind, TargetingPlugin, TargetingPolicies};
 
 use crate::{
-    Owner,
+    GameSceneId, Owner, Player,
+    map::buildings::Building,
     networking::{UnitType, WorldDirection},
     server::entities::{ProjectileRange, Sight, Unit},
 };
 
 use super::{
-    entities::{MeleeRange, health::Health},
-    physics::PushBack,
+    buildings::recruiting::FlagAssignment,
+    entities::{MeleeRange, commander::ArmyFlagAssignments, health::Health},
+    physics::{PushBack, army_slot::ArmySlot, attachment::AttachedTo, spatial::SpatialIndex},
+    players::team::Factions,
 };
 
 mod attack;
+pub mod debug;
+pub mod formation;
+pub mod morale;
 mod movement;
+pub mod targeting;
 
 #[derive(Debug, Deref, DerefMut, Component, Default)]
 pub struct FollowOffset(pub Vec2);
@@ -29,26 +41,68 @@ pub enum UnitBehaviour {
     FollowFlag,
     Idle,
     Attack(WorldDirection),
+    /// Stays at `x` and only engages enemies within `leash` of it.
+    HoldPosition {
+        x: f32,
+        leash: f32,
+    },
+    /// Guards `building` and returns to it after chasing enemies up to `leash` away.
+    DefendBuilding {
+        building: Entity,
+        leash: f32,
+    },
+    /// Pushes to the end of the scene without waiting for the rest of the army.
+    Charge(WorldDirection),
 }
 
 #[derive(Debug, Component, Default, Clone)]
 pub enum BanditBehaviour {
+    /// Roams around and attacks every enemy in [`Sight`].
     #[default]
     Aggressive,
+    /// Stays at `post` and only attacks enemies within `leash` of it.
+    Guard { post: f32, leash: f32 },
+    /// Walks back and forth between `from` and `to`, attacking enemies on the way.
+    Patrol { from: f32, to: f32 },
+    /// Fights like [`BanditBehaviour::Aggressive`], but runs to the scene edge
+    /// once its hitpoints drop to `threshold`.
+    Flee { threshold: f32 },
+    /// Stays hidden until an enemy army comes into [`Sight`], then turns aggressive.
+    Ambush,
+    /// Marches in `direction` through a player's base, attacking every enemy and building on
+    /// the way.
+    Raid(WorldDirection),
 }
 
+/// Marks a hidden bandit of a [`BanditBehaviour::Ambush`], clients don't render it.
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
+pub struct InAmbush;
+
+#[derive(Component)]
+struct Fleeing;
+
 pub struct AIPlugin;
 
 impl Plugin for AIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((BehavePlugin::default(), AIAttackPlugin, AIMovementPlugin))
-            .add_observer(on_insert_unit_behaviour)
-            .add_observer(on_insert_bandit_behaviour)
-            .add_observer(push_back_check)
-            .add_observer(determine_target)
-            .add_observer(check_target_in_melee_range)
-            .add_observer(check_target_in_projectile_range)
-            .add_systems(FixedPostUpdate, remove_target_if_out_of_sight);
+        app.add_plugins((
+            BehavePlugin::default(),
+            AIAttackPlugin,
+            AIMovementPlugin,
+            AIDebugPlugin,
+            TargetingPlugin,
+            FormationPlugin,
+            MoralePlugin,
+        ))
+        .add_observer(on_insert_unit_behaviour)
+        .add_observer(on_insert_bandit_behaviour)
+        .add_observer(push_back_check)
+        .add_observer(determine_target)
+        .add_observer(check_target_in_melee_range)
+        .add_observer(check_target_in_projectile_range)
+        .add_observer(check_intruder_near_post)
+        .add_systems(FixedUpdate, (start_fleeing, spring_ambush))
+        .add_systems(FixedPostUpdate, remove_target_if_out_of_sight);
     }
 }
 
@@ -64,13 +118,54 @@ struct WalkIntoRange;
 #[derive(Component, Clone, Deref)]
 struct WalkingInDirection(WorldDirection);
 
+#[allow(clippy::type_complexity)]
 fn on_insert_unit_behaviour(
     trigger: On<Insert, UnitBehaviour>,
-    query: Query<(&UnitBehaviour, &Unit)>,
+    query: Query<(
+        &UnitBehaviour,
+        &Unit,
+        &MeleeRange,
+        Option<&ProjectileRange>,
+        Option<&FlagAssignment>,
+    )>,
+    attached: Query<&AttachedTo>,
+    slots: Query<&ArmySlot>,
+    armies: Query<&ArmyFlagAssignments>,
+    morale: Query<&MoraleState>,
+    transforms: Query<&Transform>,
     mut commands: Commands,
 ) -> Result {
     let entity = trigger.entity;
-    let (behaviour, unit) = query.get(entity)?;
+    let (behaviour, unit, melee_range, projectile_range, flag_assignment) = query.get(entity)?;
+
+    // Routing units keep running, their tree is rebuilt once they rally.
+    if let Ok(MoraleState::Routing) = morale.get(entity) {
+        return Ok(());
+    }
+
+    if let UnitBehaviour::Attack(direction) = behaviour
+        && let Some((commander, position)) =
+            formation_of(entity, unit, flag_assignment, &attached, &slots, &armies)
+    {
+        let tree = formation_tree(
+            entity,
+            commander,
+            position,
+            *direction,
+            projectile_range.is_some(),
+        );
+
+        commands
+            .entity(entity)
+            .insert(reach(**melee_range, projectile_range.map(|range| **range)))
+            .try_remove::<Leash>()
+            .despawn_related::<BehaveSources>()
+            .with_child((
+                BehaveTree::new(tree).with_logging(false),
+                BehaveTarget(entity),
+            ));
+        return Ok(());
+    }
 
     let mut attack_chain: Vec<Tree<Behave>> = Vec::new();
 
@@ -104,7 +199,7 @@ fn on_insert_unit_behaviour(
         ));
     }
 
-    if let UnitBehaviour::Idle | UnitBehaviour::Attack(_) = behaviour {
+    if !matches!(behaviour, UnitBehaviour::FollowFlag) {
         attack_chain.push(behave!(Behave::spawn_named(
             "Walking to target",
             (
@@ -116,16 +211,41 @@ fn on_insert_unit_behaviour(
         )));
     }
 
-    let stance = match behaviour {
-        UnitBehaviour::Idle | UnitBehaviour::FollowFlag => behave!(Behave::spawn_named(
-            "Following flag",
+    let post = match behaviour {
+        UnitBehaviour::HoldPosition { x, leash } => Some((
+            "Holding position",
+            Leash {
+                post: *x,
+                distance: *leash,
+            },
+        )),
+        UnitBehaviour::DefendBuilding { building, leash } => Some((
+            "Defending building",
+            Leash {
+                post: transforms.get(*building)?.translation.x,
+                distance: *leash,
+            },
+        )),
+        _ => None,
+    };
+    let leash = post.map(|(_, leash)| leash);
+
+    // Units on a post only engage enemies within their leash and walk back afterwards.
+    let engage = match leash {
+        Some(_) => behave!(Behave::trigger(IntruderNearPost)),
+        None => behave!(Behave::trigger(DetermineTarget)),
+    };
+
+    let stance = match (behaviour, post) {
+        (_, Some((stance_name, leash))) => behave!(Behave::spawn_named(
+            stance_name,
             (
-                FollowFlag,
-                BehaveInterrupt::by(DetermineTarget).or(BeingPushed),
+                GuardPost(leash.post),
+                BehaveInterrupt::by(IntruderNearPost).or(BeingPushed),
                 BehaveTarget(entity)
             )
         )),
-        UnitBehaviour::Attack(direction) => behave!(
+        (UnitBehaviour::Attack(direction), _) => behave!(
             Behave::Sequence => {
                 Behave::spawn((
                     Name::new("Wait until unit can attack in direction"),
@@ -140,13 +260,29 @@ fn on_insert_unit_behaviour(
                 ))
             }
         ),
+        (UnitBehaviour::Charge(direction), _) => behave!(Behave::spawn_named(
+            "Charging",
+            (
+                WalkingInDirection(*direction),
+                BehaveInterrupt::by(DetermineTarget).or(BeingPushed),
+                BehaveTarget(entity)
+            )
+        )),
+        _ => behave!(Behave::spawn_named(
+            "Following flag",
+            (
+                FollowFlag,
+                BehaveInterrupt::by(DetermineTarget).or(BeingPushed),
+                BehaveTarget(entity)
+            )
+        )),
     };
 
     let tree = behave!(
         Behave::Forever => {
             Behave::Fallback => {
                 Behave::Sequence => {
-                    Behave::trigger(DetermineTarget),
+                    @ engage,
                     Behave::Fallback => {
                         ... attack_chain
                     }
@@ -156,8 +292,13 @@ fn on_insert_unit_behaviour(
         }
     );
 
-    commands
-        .entity(entity)
+    let mut entity_commands = commands.entity(entity);
+    match leash {
+        Some(leash) => entity_commands.insert(leash),
+        None => entity_commands.try_remove::<Leash>(),
+    };
+    entity_commands
+        .try_remove::<Reach>()
         .despawn_related::<BehaveSources>()
         .with_child((
             BehaveTree::new(tree).with_logging(false),
@@ -169,31 +310,100 @@ fn on_insert_unit_behaviour(
 
 fn on_insert_bandit_behaviour(
     trigger: On<Insert, BanditBehaviour>,
-    query: Query<&BanditBehaviour>,
+    query: Query<(&BanditBehaviour, Has<Fleeing>)>,
+    morale: Query<&MoraleState>,
     mut commands: Commands,
 ) -> Result {
     let entity = trigger.entity;
-    let behaviour = query.get(entity)?;
+    let (behaviour, fleeing) = query.get(entity)?;
 
-    let stance = match behaviour {
-        BanditBehaviour::Aggressive => behave!(Behave::spawn_named(
-            "Roaming",
-            (
-                Roam::default(),
-                BehaveInterrupt::by(DetermineTarget).or(BeingPushed),
-                BehaveTarget(entity)
-            )
-        )),
-    };
+    if let Ok(MoraleState::Routing) = morale.get(entity) {
+        return Ok(());
+    }
 
     let attack_chain = attack_and_walk_in_range(entity);
 
-    let tree = behave!(
-        Behave::Forever => {
+    let stance = match behaviour {
+        BanditBehaviour::Flee { .. } if fleeing => behave!(Behave::spawn_named(
+            "Fleeing",
+            (FleeToEdge, BehaveTarget(entity))
+        )),
+        BanditBehaviour::Aggressive | BanditBehaviour::Flee { .. } => behave!(
             Behave::Fallback => {
                 @ attack_chain,
-                @ stance
+                Behave::spawn_named(
+                    "Roaming",
+                    (
+                        Roam::default(),
+                        BehaveInterrupt::by(DetermineTarget).or(BeingPushed),
+                        BehaveTarget(entity)
+                    )
+                )
             }
+        ),
+        BanditBehaviour::Guard { post, leash } => {
+            commands.entity(entity).insert(Leash {
+                post: *post,
+                distance: *leash,
+            });
+            behave!(
+                Behave::Fallback => {
+                    Behave::Sequence => {
+                        Behave::trigger(IntruderNearPost),
+                        @ attack_chain
+                    },
+                    Behave::spawn_named(
+                        "Guarding post",
+                        (
+                            GuardPost(*post),
+                            BehaveInterrupt::by(IntruderNearPost).or(BeingPushed),
+                            BehaveTarget(entity)
+                        )
+                    )
+                }
+            )
+        }
+        BanditBehaviour::Patrol { from, to } => behave!(
+            Behave::Fallback => {
+                @ attack_chain,
+                Behave::spawn_named(
+                    "Patrolling",
+                    (
+                        Patrol::between(*from, *to),
+                        BehaveInterrupt::by(DetermineTarget).or(BeingPushed),
+                        BehaveTarget(entity)
+                    )
+                )
+            }
+        ),
+        BanditBehaviour::Raid(direction) => {
+            commands.entity(entity).try_remove::<Leash>();
+            behave!(
+                Behave::Fallback => {
+                    @ attack_chain,
+                    Behave::spawn_named(
+                        "Raiding",
+                        (
+                            WalkingInDirection(*direction),
+                            BehaveInterrupt::by(DetermineTarget).or(BeingPushed),
+                            BehaveTarget(entity)
+                        )
+                    )
+                }
+            )
+        }
+        BanditBehaviour::Ambush => {
+            commands.entity(entity).insert(InAmbush);
+            behave!(Behave::spawn_named(
+                "Lying in ambush",
+                (LieInWait, BehaveTarget(entity))
+            ))
+        }
+    };
+
+    let tree = behave!(
+        Behave::Forever => {
+            @ stance
         }
     );
 
@@ -257,6 +467,17 @@ struct TargetInMeleeRange;
 #[derive(Event, Clone)]
 struct TargetInProjectileRange;
 
+/// Succeeds if a guard has an enemy within the leash of its post as target.
+#[derive(Event, Clone)]
+struct IntruderNearPost;
+
+/// Keeps a unit guarding a post from engaging enemies further than `distance` from it.
+#[derive(Component, Clone, Copy)]
+struct Leash {
+    post: f32,
+    distance: f32,
+}
+
 #[derive(Component, Clone, Deref)]
 struct WaitToAttack(WorldDirection);
 
@@ -289,39 +510,144 @@ fn push_back_check(
     Ok(())
 }
 
+type TargetCandidates<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static Owner,
+        &'static Health,
+        Has<Player>,
+        Has<Building>,
+        Option<&'static TargetedBy>,
+    ),
+>;
+
 fn determine_target(
     trigger: On<BehaveTrigger<DetermineTarget>>,
-    query: Query<(&Transform, &Owner, &Sight, Option<&Target>)>,
-    others: Query<(Entity, &Transform, &Owner), With<Health>>,
+    query: Query<(
+        &Transform,
+        &Owner,
+        &Sight,
+        &Unit,
+        &GameSceneId,
+        Option<&Target>,
+        Option<&RecentDamage>,
+        Option<&Reach>,
+        Option<&Leash>,
+    )>,
+    others: TargetCandidates,
+    policies: Res<TargetingPolicies>,
+    factions: Factions,
+    index: Res<SpatialIndex>,
     mut commands: Commands,
 ) -> Result {
     let ctx = trigger.event().ctx();
     let unit_entity = ctx.target_entity();
-    let (transform, owner, sight, maybe_target) = query.get(unit_entity)?;
+    let (transform, owner, sight, unit, game_scene_id, maybe_target, recent_damage, reach, leash) =
+        query.get(unit_entity)?;
+    let policy = policies.get(unit.unit_type);
+    let radius = reach.map_or(**sight, |reach| reach.min(**sight));
+
+    let best = index
+        .near(*game_scene_id, transform.translation.x, radius)
+        .into_iter()
+        .filter_map(|candidate| others.get(candidate).ok())
+        .filter(|(.., other_owner, _, _, _, _)| factions.is_enemy(owner, other_owner))
+        .filter_map(
+            |(other_entity, other_transform, _, health, is_player, is_building, targeted_by)| {
+                let distance = transform
+                    .translation
+                    .truncate()
+                    .distance(other_transform.translation.truncate());
+                if distance > radius {
+                    return None;
+                }
+                // Units on a post never get pulled past their leash by a better scored enemy.
+                if leash.is_some_and(|leash| {
+                    (other_transform.translation.x - leash.post).abs() > leash.distance
+                }) {
+                    return None;
+                }
+
+                let current = maybe_target.is_some_and(|target| **target == other_entity);
+                let candidate = TargetCandidate {
+                    kind: TargetKind::of(is_player, is_building),
+                    distance,
+                    sight: radius,
+                    attackers: TargetCandidate::attackers(targeted_by, current),
+                    recent_damage: recent_damage
+                        .map(|recent| recent.by(other_entity))
+                        .unwrap_or(0.),
+                    hitpoints: health.hitpoints,
+                    current,
+                };
+                Some((other_entity, policy.score(&candidate)))
+            },
+        )
+        .max_by(|(.., a), (.., b)| a.total_cmp(b));
+
+    match best {
+        Some((best_enemy, ..)) => {
+            if maybe_target.is_none_or(|target| **target != best_enemy) {
+                commands.entity(unit_entity).insert(Target(best_enemy));
+            }
+            commands.trigger(ctx.success());
+        }
+        None => {
+            // Units in formation let go of targets that left their reach and regroup.
+            if reach.is_some() && maybe_target.is_some() {
+                commands.entity(unit_entity).try_remove::<Target>();
+            }
+            commands.trigger(ctx.failure());
+        }
+    }
+    Ok(())
+}
 
-    if maybe_target.is_some() {
-        commands.trigger(ctx.success());
+fn check_intruder_near_post(
+    trigger: On<BehaveTrigger<IntruderNearPost>>,
+    query: Query<(&Leash, &Owner, &GameSceneId, Option<&Target>)>,
+    others: Query<(Entity, &Transform, &Owner), With<Health>>,
+    factions: Factions,
+    index: Res<SpatialIndex>,
+    mut commands: Commands,
+) -> Result {
+    let ctx = trigger.ctx();
+    let guard = ctx.target_entity();
+    let Ok((&Leash { post, distance }, owner, game_scene_id, maybe_target)) = query.get(guard)
+    else {
+        commands.trigger(ctx.failure());
         return Ok(());
+    };
+    let in_leash = |transform: &Transform| (transform.translation.x - post).abs() <= distance;
+
+    if let Some(target) = maybe_target {
+        if let Ok((_, transform, _)) = others.get(**target)
+            && in_leash(transform)
+        {
+            commands.trigger(ctx.success());
+            return Ok(());
+        }
+        commands.entity(guard).try_remove::<Target>();
     }
 
-    let nearest = others
-        .iter()
-        .filter(|(.., other_owner)| other_owner.ne(&owner))
-        .map(|(other_entity, other_transform, _)| {
-            (
-                other_entity,
-                transform
-                    .translation
-                    .truncate()
-                    .distance(other_transform.translation.truncate()),
-            )
-        })
-        .filter(|(.., distance)| *distance <= **sight)
-        .min_by(|(.., a), (.., b)| a.total_cmp(b));
+    let nearest = index
+        .near(*game_scene_id, post, distance)
+        .into_iter()
+        .filter_map(|candidate| others.get(candidate).ok())
+        .filter(|(.., other_owner)| factions.is_enemy(owner, other_owner))
+        .filter(|(_, other_transform, _)| in_leash(other_transform))
+        .min_by(|(_, a, _), (_, b, _)| {
+            (a.translation.x - post)
+                .abs()
+                .total_cmp(&(b.translation.x - post).abs())
+        });
 
     match nearest {
-        Some((nearest_enemy, ..)) => {
-            commands.entity(unit_entity).insert(Target(nearest_enemy));
+        Some((intruder, ..)) => {
+            commands.entity(guard).insert(Target(intruder));
             commands.trigger(ctx.success());
         }
         None => commands.trigger(ctx.failure()),
@@ -399,3 +725,60 @@ fn remove_target_if_out_of_sight(
     }
     Ok(())
 }
+
+fn start_fleeing(
+    query: Query<(Entity, &BanditBehaviour, &Health), (Changed<Health>, Without<Fleeing>)>,
+    mut commands: Commands,
+) {
+    for (entity, behaviour, health) in query.iter() {
+        let BanditBehaviour::Flee { threshold } = behaviour else {
+            continue;
+        };
+        if health.hitpoints > *threshold {
+            continue;
+        }
+
+        // Reinserting the behaviour rebuilds the tree with the fleeing stance.
+        commands
+            .entity(entity)
+            .try_remove::<Target>()
+            .insert((Fleeing, behaviour.clone()));
+    }
+}
+
+/// Only units spring an ambush, a lone king walks past unharmed.
+fn spring_ambush(
+    ambushers: Query<(Entity, &Transform, &Sight, &Owner, &GameSceneId), With<InAmbush>>,
+    others: Query<(&Transform, &Owner, &GameSceneId), (With<Health>, With<Unit>)>,
+    factions: Factions,
+    mut commands: Commands,
+) {
+    let sprung: Vec<GameSceneId> = ambushers
+        .iter()
+        .filter(|(_, transform, sight, owner, game_scene_id)| {
+            others
+                .iter()
+                .filter(|(_, other_owner, other_scene_id)| {
+                    factions.is_enemy(owner, other_owner) && **other_scene_id == **game_scene_id
+                })
+                .any(|(other_transform, ..)| {
+                    transform
+                        .translation
+                        .truncate()
+                        .distance(other_transform.translation.truncate())
+                        <= ***sight
+                })
+        })
+        .map(|(.., game_scene_id)| *game_scene_id)
+        .collect();
+
+    // Once one bandit spots the enemy, the whole ambush in the scene attacks.
+    for (entity, .., game_scene_id) in ambushers.iter() {
+        if sprung.contains(game_scene_id) {
+            commands
+                .entity(entity)
+                .remove::<InAmbush>()
+                .insert(BanditBehaviour::Aggressive);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use bevy::prelude::*;
+
+use bevy_behave::{Behave, behave, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GameSceneId, Owner,
+    networking::UnitType,
+    server::{
+        buildings::recruiting::{FlagAssignment, FlagUnits},
+        entities::{
+            Sight, Unit,
+            commander::ArmyFlagAssignments,
+            health::{DamageApplied, Health, UnitDied},
+        },
+        physics::{
+            movement::{NoWalkZone, RandomVelocityMul, Speed, Velocity},
+            spatial::SpatialIndex,
+        },
+        players::team::Factions,
+    },
+};
+
+use super::{
+    BanditBehaviour, BehaveSources, BehaveTarget, Target, UnitBehaviour, movement::walk_towards,
+};
+
+const MAX_MORALE: f32 = 100.;
+
+/// Below this units waver, below [`ROUTING_MORALE`] they break and run.
+const WAVERING_MORALE: f32 = 50.;
+const ROUTING_MORALE: f32 = 25.;
+/// Routing units only return to the fight once their morale recovered to this.
+const RALLY_MORALE: f32 = 40.;
+
+/// Morale lost per point of damage taken.
+const DAMAGE_MORALE: f32 = 0.5;
+/// Morale lost when an ally within [`ALLY_DEATH_DISTANCE`] dies.
+const ALLY_DEATH_MORALE: f32 = 8.;
+const ALLY_DEATH_DISTANCE: f32 = 100.;
+/// Morale lost by every unit of an army when its commander dies.
+const COMMANDER_DEATH_MORALE: f32 = 30.;
+
+/// Morale lost per second while more enemies than [`OUTNUMBERED_RATIO`] times the allies are in sight.
+const OUTNUMBERED_RATE: f32 = 6.;
+const OUTNUMBERED_RATIO: f32 = 1.5;
+/// Morale gained per second near the unit's flag or king.
+const RALLY_RATE: f32 = 10.;
+const RALLY_DISTANCE: f32 = 80.;
+/// Morale gained per second with no enemy in sight.
+const RECOVERY_RATE: f32 = 3.;
+
+/// Damage taken by routing units is multiplied by this.
+pub const ROUTING_DAMAGE_MULTIPLIER: f32 = 1.5;
+
+pub struct MoralePlugin;
+
+impl Plugin for MoralePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MoraleTimer>()
+            .add_observer(lose_morale_on_death)
+            .add_observer(reset_morale_on_death)
+            .add_systems(
+                FixedUpdate,
+                (
+                    lose_morale_on_damage,
+                    update_morale,
+                    update_morale_state,
+                    start_routing,
+                    stop_routing,
+                    rout,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Will to keep fighting, from 0 to 100.
+#[derive(Component, Clone, Copy, Debug, Deref, DerefMut)]
+#[require(MoraleState)]
+pub struct Morale(f32);
+
+impl Default for Morale {
+    fn default() -> Self {
+        Self(MAX_MORALE)
+    }
+}
+
+impl Morale {
+    fn change(&mut self, amount: f32) {
+        self.0 = (self.0 + amount).clamp(0., MAX_MORALE);
+    }
+}
+
+/// Replicated to clients to show wavering and routing squads.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoraleState {
+    #[default]
+    Steady,
+    Wavering,
+    Routing,
+}
+
+impl MoraleState {
+    pub fn damage_multiplier(&self) -> f32 {
+        match self {
+            MoraleState::Routing => ROUTING_DAMAGE_MULTIPLIER,
+            MoraleState::Steady | MoraleState::Wavering => 1.,
+        }
+    }
+}
+
+/// Marks a unit whose behaviour tree was replaced while routing.
+#[derive(Component)]
+struct Routed;
+
+/// Runs to the unit's flag, or the scene edge without one.
+#[derive(Component, Clone)]
+struct Rout;
+
+#[derive(Resource, Deref, DerefMut)]
+struct MoraleTimer(Timer);
+
+impl Default for MoraleTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0.5, TimerMode::Repeating))
+    }
+}
+
+fn lose_morale_on_damage(
+    mut damage: MessageReader<DamageApplied>,
+    mut query: Query<&mut Morale, With<Health>>,
+) {
+    for event in damage.read() {
+        if let Ok(mut morale) = query.get_mut(event.target_entity) {
+            morale.change(-event.damage * DAMAGE_MORALE);
+        }
+    }
+}
+
+fn lose_morale_on_death(
+    trigger: On<UnitDied>,
+    dead: Query<(&Transform, &GameSceneId, Option<&ArmyFlagAssignments>)>,
+    mut allies: Query<(&Transform, &Owner, &mut Morale), With<Health>>,
+    flag_units: Query<&FlagUnits>,
+    index: Res<SpatialIndex>,
+) {
+    let death = trigger.event();
+    let Ok((transform, game_scene_id, army)) = dead.get(death.entity) else {
+        return;
+    };
+    let x = transform.translation.x;
+
+    for ally in index.near(*game_scene_id, x, ALLY_DEATH_DISTANCE) {
+        if ally == death.entity {
+            continue;
+        }
+        let Ok((ally_transform, owner, mut morale)) = allies.get_mut(ally) else {
+            continue;
+        };
+        if owner.ne(&death.owner) || (ally_transform.translation.x - x).abs() > ALLY_DEATH_DISTANCE
+        {
+            continue;
+        }
+        morale.change(-ALLY_DEATH_MORALE);
+    }
+
+    let (UnitType::Commander, Some(army)) = (death.unit_type, army) else {
+        return;
+    };
+    for flag in army.flags.iter().flatten() {
+        let Ok(units) = flag_units.get(*flag) else {
+            continue;
+        };
+        for unit in units.iter() {
+            if let Ok((.., mut morale)) = allies.get_mut(unit) {
+                morale.change(-COMMANDER_DEATH_MORALE);
+            }
+        }
+    }
+}
+
+/// Corpses don't keep showing the state the unit died in.
+fn reset_morale_on_death(trigger: On<UnitDied>, mut query: Query<&mut MoraleState>) {
+    if let Ok(mut state) = query.get_mut(trigger.event().entity) {
+        state.set_if_neq(MoraleState::Steady);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_morale(
+    mut timer: ResMut<MoraleTimer>,
+    mut units: Query<
+        (
+            &mut Morale,
+            &Transform,
+            &Owner,
+            &GameSceneId,
+            &Sight,
+            Option<&FlagAssignment>,
+        ),
+        With<Health>,
+    >,
+    others: Query<(&Transform, &Owner), (With<Unit>, With<Health>)>,
+    transforms: Query<&Transform>,
+    factions: Factions,
+    index: Res<SpatialIndex>,
+    time: Res<Time>,
+) {
+    timer.tick(time.delta());
+    if !timer.just_finished() {
+        return;
+    }
+    let secs = timer.duration().as_secs_f32();
+
+    for (mut morale, transform, owner, game_scene_id, sight, flag) in units.iter_mut() {
+        let x = transform.translation.x;
+
+        let (allies, enemies) = index
+            .near(*game_scene_id, x, **sight)
+            .into_iter()
+            .filter_map(|other| others.get(other).ok())
+            .filter(|(other_transform, _)| (other_transform.translation.x - x).abs() <= **sight)
+            .fold((0, 0), |(allies, enemies), (_, other_owner)| match factions
+                .is_allied(other_owner, owner)
+            {
+                true => (allies + 1, enemies),
+                false => (allies, enemies + 1),
+            });
+
+        let near_rally_point = flag
+            .map(|flag| **flag)
+            .into_iter()
+            .chain(owner.entity().ok())
+            .filter_map(|rally_point| transforms.get(rally_point).ok())
+            .any(|rally_point| (rally_point.translation.x - x).abs() <= RALLY_DISTANCE);
+
+        if enemies as f32 > allies as f32 * OUTNUMBERED_RATIO {
+            morale.change(-OUTNUMBERED_RATE * secs);
+        }
+        if near_rally_point {
+            morale.change(RALLY_RATE * secs);
+        } else if enemies == 0 {
+            morale.change(RECOVERY_RATE * secs);
+        }
+    }
+}
+
+fn update_morale_state(mut query: Query<(&Morale, &mut MoraleState), Changed<Morale>>) {
+    for (morale, mut state) in query.iter_mut() {
+        let new_state = match (**morale, *state) {
+            (value, _) if value < ROUTING_MORALE => MoraleState::Routing,
+            (value, MoraleState::Routing) if value < RALLY_MORALE => MoraleState::Routing,
+            (value, _) if value < WAVERING_MORALE => MoraleState::Wavering,
+            _ => MoraleState::Steady,
+        };
+        state.set_if_neq(new_state);
+    }
+}
+
+fn start_routing(
+    query: Query<(Entity, &MoraleState), (Changed<MoraleState>, With<Health>, Without<Routed>)>,
+    mut commands: Commands,
+) {
+    for (entity, state) in query.iter() {
+        let MoraleState::Routing = state else {
+            continue;
+        };
+
+        let tree = behave!(
+            Behave::Forever => {
+                Behave::spawn_named("Routing", (Rout, BehaveTarget(entity)))
+            }
+        );
+
+        commands
+            .entity(entity)
+            .insert(Routed)
+            .try_remove::<Target>()
+            .despawn_related::<BehaveSources>()
+            .with_child((
+                BehaveTree::new(tree).with_logging(false),
+                BehaveTarget(entity),
+            ));
+    }
+}
+
+fn stop_routing(
+    query: Query<
+        (
+            Entity,
+            &MoraleState,
+            Option<&UnitBehaviour>,
+            Option<&BanditBehaviour>,
+        ),
+        (Changed<MoraleState>, With<Routed>),
+    >,
+    mut commands: Commands,
+) {
+    for (entity, state, unit_behaviour, bandit_behaviour) in query.iter() {
+        if let MoraleState::Routing = state {
+            continue;
+        }
+
+        // Reinserting the behaviour rebuilds the tree the unit had before routing.
+        let mut entity = commands.entity(entity);
+        entity.remove::<Routed>();
+        if let Some(behaviour) = unit_behaviour {
+            entity.insert(behaviour.clone());
+        }
+        if let Some(behaviour) = bandit_behaviour {
+            entity.insert(behaviour.clone());
+        }
+    }
+}
+
+fn rout(
+    query: Query<&BehaveCtx, With<Rout>>,
+    mut unit: Query<(
+        &mut Velocity,
+        &Transform,
+        &GameSceneId,
+        &RandomVelocityMul,
+        &Speed,
+        Option<&FlagAssignment>,
+    )>,
+    transforms: Query<&Transform, Without<NoWalkZone>>,
+    no_walk_zones: Query<(&Transform, &GameSceneId), With<NoWalkZone>>,
+) -> Result {
+    for ctx in query.iter() {
+        let (mut velocity, transform, game_scene_id, rand_velocity_mul, speed, flag) =
+            unit.get_mut(ctx.target_entity())?;
+        let x = transform.translation.x;
+
+        let flag_x = flag
+            .and_then(|flag| transforms.get(**flag).ok())
+            .map(|flag| flag.translation.x);
+        let edge_x = || {
+            no_walk_zones
+                .iter()
+                .filter(|(_, zone_game_scene_id)| zone_game_scene_id.eq(&game_scene_id))
+                .map(|(zone_transform, _)| zone_transform.translation.x)
+                .min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()))
+                .map(|edge| edge - (edge - x).signum() * RALLY_DISTANCE)
+        };
+
+        match flag_x.or_else(edge_x) {
+            Some(target) => {
+                walk_towards(&mut velocity, x, target, **speed * **rand_velocity_mul);
+            }
+            None => velocity.0.x = 0.,
+        }
+    }
+    Ok(())
+}
@@ -5,13 +5,14 @@ use bevy_behave::prelude::BehaveCtx;
 use super::{FollowOffset, Target, WalkIntoRange, WalkingInDirection};
 
 use crate::{
+    GameSceneId,
     networking::UnitType,
     server::{
         buildings::recruiting::FlagAssignment,
         entities::{MeleeRange, Unit},
         physics::{
             attachment::AttachedTo,
-            movement::{RandomVelocityMul, Speed, Velocity},
+            movement::{NoWalkZone, RandomVelocityMul, Speed, Velocity},
         },
     },
 };
@@ -28,19 +29,69 @@ impl Default for Roam {
     }
 }
 
+/// Walks back to the post at the given x position and stays there.
+#[derive(Component, Clone, Deref)]
+pub struct GuardPost(pub f32);
+
+#[derive(Component, Clone)]
+pub struct Patrol {
+    from: f32,
+    to: f32,
+    returning: bool,
+}
+
+impl Patrol {
+    pub fn between(from: f32, to: f32) -> Self {
+        Self {
+            from,
+            to,
+            returning: false,
+        }
+    }
+}
+
+/// Runs to the nearest edge of the scene and stays there.
+#[derive(Component, Clone)]
+pub struct FleeToEdge;
+
+#[derive(Component, Clone)]
+pub struct LieInWait;
+
 pub struct AIMovementPlugin;
 
 impl Plugin for AIMovementPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             FixedUpdate,
-            (follow_flag, roam, walk_into_range, walk_in_direction),
+            (
+                follow_flag,
+                roam,
+                walk_into_range,
+                walk_in_direction,
+                guard_post,
+                patrol,
+                flee_to_edge,
+                lie_in_wait,
+            ),
         );
     }
 }
 
 const MOVE_EPSILON: f32 = 1.;
 
+/// Distance fleeing units keep to the [`NoWalkZone`] at the scene edge.
+const FLEE_EDGE_MARGIN: f32 = 20.;
+
+pub(super) fn walk_towards(velocity: &mut Velocity, x: f32, target: f32, speed: f32) -> bool {
+    if (x - target).abs() <= MOVE_EPSILON {
+        velocity.0.x = 0.;
+        return true;
+    }
+
+    velocity.0.x = (target - x).signum() * speed;
+    false
+}
+
 fn follow_flag(
     query: Query<&BehaveCtx, With<FollowFlag>>,
     mut unit: Query<(
@@ -176,3 +227,91 @@ fn walk_in_direction(
     }
     Ok(())
 }
+
+fn guard_post(
+    query: Query<(&BehaveCtx, &GuardPost)>,
+    mut unit: Query<(&mut Velocity, &Transform, &RandomVelocityMul, &Speed)>,
+) -> Result {
+    for (ctx, post) in query.iter() {
+        let (mut velocity, transform, rand_velocity_mul, speed) =
+            unit.get_mut(ctx.target_entity())?;
+
+        walk_towards(
+            &mut velocity,
+            transform.translation.x,
+            **post,
+            **speed * **rand_velocity_mul,
+        );
+    }
+    Ok(())
+}
+
+fn patrol(
+    mut query: Query<(&BehaveCtx, &mut Patrol)>,
+    mut unit: Query<(&mut Velocity, &Transform, &RandomVelocityMul, &Speed)>,
+) -> Result {
+    for (ctx, mut patrol) in query.iter_mut() {
+        let (mut velocity, transform, rand_velocity_mul, speed) =
+            unit.get_mut(ctx.target_entity())?;
+
+        let waypoint = match patrol.returning {
+            true => patrol.from,
+            false => patrol.to,
+        };
+
+        let arrived = walk_towards(
+            &mut velocity,
+            transform.translation.x,
+            waypoint,
+            **speed * **rand_velocity_mul,
+        );
+
+        if arrived {
+            patrol.returning = !patrol.returning;
+        }
+    }
+    Ok(())
+}
+
+fn flee_to_edge(
+    query: Query<&BehaveCtx, With<FleeToEdge>>,
+    mut unit: Query<(
+        &mut Velocity,
+        &Transform,
+        &GameSceneId,
+        &RandomVelocityMul,
+        &Speed,
+    )>,
+    no_walk_zones: Query<(&Transform, &GameSceneId), With<NoWalkZone>>,
+) -> Result {
+    for ctx in query.iter() {
+        let (mut velocity, transform, game_scene_id, rand_velocity_mul, speed) =
+            unit.get_mut(ctx.target_entity())?;
+        let x = transform.translation.x;
+
+        let Some(edge) = no_walk_zones
+            .iter()
+            .filter(|(_, zone_game_scene_id)| zone_game_scene_id.eq(&game_scene_id))
+            .map(|(zone_transform, _)| zone_transform.translation.x)
+            .min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()))
+        else {
+            velocity.0.x = 0.;
+            continue;
+        };
+
+        let target = edge - (edge - x).signum() * FLEE_EDGE_MARGIN;
+        walk_towards(&mut velocity, x, target, **speed * **rand_velocity_mul);
+    }
+    Ok(())
+}
+
+fn lie_in_wait(
+    query: Query<&BehaveCtx, With<LieInWait>>,
+    mut unit: Query<&mut Velocity>,
+) -> Result {
+    for ctx in query.iter() {
+        let mut velocity = unit.get_mut(ctx.target_entity())?;
+        velocity.0.x = 0.;
+    }
+    Ok(())
+}
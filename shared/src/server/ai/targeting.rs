@@ -0,0 +1,211 @@
+use bevy::prelude::*;
+
+use crate::{
+    enum_map::*,
+    networking::UnitType,
+    server::entities::health::{DamageApplied, Health},
+};
+
+use super::TargetedBy;
+
+/// How long damage taken from an attacker counts towards retaliating against it.
+const RECENT_DAMAGE_SECS: f32 = 5.;
+
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TargetingPolicies>()
+            .add_systems(FixedUpdate, record_recent_damage);
+    }
+}
+
+/// Weights used to score potential targets, the candidate with the highest score is attacked.
+#[derive(Debug, Clone, Copy)]
+pub struct TargetingPolicy {
+    /// Score lost by a candidate at the edge of the unit's sight.
+    pub distance: f32,
+    /// Base score of units.
+    pub unit: f32,
+    /// Base score of kings.
+    pub king: f32,
+    /// Base score of buildings.
+    pub building: f32,
+    /// Score lost for every other unit already attacking the candidate.
+    pub focus: f32,
+    /// Score gained per point of damage the candidate recently dealt to the unit.
+    pub retaliation: f32,
+    /// Score gained by a candidate without hitpoints, halved at 100 hitpoints.
+    pub low_health: f32,
+    /// Score a new candidate needs above the current target to be picked instead.
+    pub hysteresis: f32,
+}
+
+impl Default for TargetingPolicy {
+    fn default() -> Self {
+        Self {
+            distance: 10.,
+            unit: 5.,
+            king: 4.,
+            building: 0.,
+            focus: 1.5,
+            retaliation: 0.5,
+            low_health: 2.,
+            hysteresis: 3.,
+        }
+    }
+}
+
+impl TargetingPolicy {
+    pub fn score(&self, candidate: &TargetCandidate) -> f32 {
+        let kind = match candidate.kind {
+            TargetKind::Unit => self.unit,
+            TargetKind::King => self.king,
+            TargetKind::Building => self.building,
+        };
+
+        let hysteresis = match candidate.current {
+            true => self.hysteresis,
+            false => 0.,
+        };
+
+        kind - self.distance * candidate.distance / candidate.sight
+            - self.focus * candidate.attackers as f32
+            + self.retaliation * candidate.recent_damage
+            + self.low_health / (1. + candidate.hitpoints / 100.)
+            + hysteresis
+    }
+}
+
+/// Targeting policy of every unit type.
+#[derive(Resource, Deref, DerefMut)]
+pub struct TargetingPolicies(pub EnumMap<UnitType, TargetingPolicy>);
+
+impl Default for TargetingPolicies {
+    fn default() -> Self {
+        Self(EnumMap::new(|unit_type| match unit_type {
+            UnitType::Shieldwarrior => TargetingPolicy {
+                retaliation: 1.,
+                ..default()
+            },
+            UnitType::Pikeman => TargetingPolicy {
+                king: 6.,
+                ..default()
+            },
+            UnitType::Archer => TargetingPolicy {
+                distance: 4.,
+                unit: 6.,
+                building: -10.,
+                focus: 1.,
+                retaliation: 0.2,
+                low_health: 4.,
+                ..default()
+            },
+            UnitType::Bandit => TargetingPolicy {
+                king: 6.,
+                focus: 0.5,
+                ..default()
+            },
+            UnitType::Commander => TargetingPolicy::default(),
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Unit,
+    King,
+    Building,
+}
+
+impl TargetKind {
+    pub fn of(is_player: bool, is_building: bool) -> Self {
+        match (is_player, is_building) {
+            (true, _) => TargetKind::King,
+            (_, true) => TargetKind::Building,
+            _ => TargetKind::Unit,
+        }
+    }
+}
+
+/// Everything a [`TargetingPolicy`] knows about a potential target.
+#[derive(Debug)]
+pub struct TargetCandidate {
+    pub kind: TargetKind,
+    pub distance: f32,
+    pub sight: f32,
+    /// Other units already attacking the candidate.
+    pub attackers: usize,
+    /// Damage the candidate dealt to the unit during the last seconds.
+    pub recent_damage: f32,
+    pub hitpoints: f32,
+    /// Whether the candidate is the current target of the unit.
+    pub current: bool,
+}
+
+impl TargetCandidate {
+    pub fn attackers(targeted_by: Option<&TargetedBy>, current: bool) -> usize {
+        let attackers = targeted_by
+            .map(|targeted_by| targeted_by.len())
+            .unwrap_or(0);
+        match current {
+            true => attackers.saturating_sub(1),
+            false => attackers,
+        }
+    }
+}
+
+struct RecentHit {
+    attacker: Entity,
+    damage: f32,
+    at: f32,
+}
+
+/// Damage taken during the last [`RECENT_DAMAGE_SECS`], by attacker.
+#[derive(Component, Default)]
+pub struct RecentDamage(Vec<RecentHit>);
+
+impl RecentDamage {
+    pub fn by(&self, attacker: Entity) -> f32 {
+        self.0
+            .iter()
+            .filter(|hit| hit.attacker == attacker)
+            .map(|hit| hit.damage)
+            .sum()
+    }
+}
+
+fn record_recent_damage(
+    mut damage: MessageReader<DamageApplied>,
+    mut query: Query<&mut RecentDamage>,
+    health: Query<(), With<Health>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let now = time.elapsed_secs();
+
+    for mut recent in query.iter_mut() {
+        recent.0.retain(|hit| now - hit.at <= RECENT_DAMAGE_SECS);
+    }
+
+    for event in damage.read() {
+        let Some(attacker) = event.attacker else {
+            continue;
+        };
+        let hit = RecentHit {
+            attacker,
+            damage: event.damage,
+            at: now,
+        };
+
+        match query.get_mut(event.target_entity) {
+            Ok(mut recent) => recent.0.push(hit),
+            Err(_) if health.contains(event.target_entity) => {
+                commands
+                    .entity(event.target_entity)
+                    .try_insert(RecentDamage(vec![hit]));
+            }
+            Err(_) => {}
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use bevy::prelude::*;
 
-use crate::{Owner, map::buildings::BuildingType, networking::Inventory};
+use crate::{
+    Owner,
+    map::buildings::BuildingType,
+    networking::Inventory,
+    server::stats::{GoldReason, GoldTransaction},
+};
 
 use super::BuildingChangeEnd;
 
@@ -35,6 +40,7 @@ pub fn enable_goldfarm(mut commands: Commands, mut events: MessageReader<Buildin
 pub fn gold_farm_output(
     mut gold_farms_query: Query<(&mut GoldFarmTimer, &Owner)>,
     mut inventory_query: Query<&mut Inventory>,
+    mut gold: MessageWriter<GoldTransaction>,
     time: Res<Time>,
 ) -> Result {
     for (mut farm_timer, owner) in &mut gold_farms_query {
@@ -45,6 +51,11 @@ pub fn gold_farm_output(
             let mut inventory = inventory_query.get_mut(owner)?;
 
             inventory.gold += GOLD_PER_TICK;
+            gold.write(GoldTransaction::earned(
+                owner,
+                GOLD_PER_TICK,
+                GoldReason::GoldFarm,
+            ));
         }
     }
     Ok(())
@@ -22,6 +22,7 @@ impl Plugin for ItemAssignmentPlugins {
     fn build(&self, app: &mut App) {
         app.add_observer(assign_item)
             .add_observer(check_start_building)
+            .add_observer(build_assignment)
             .init_resource::<ActiveBuilding>()
             .add_systems(
                 Update,
@@ -44,6 +45,13 @@ pub struct ItemAssignment {
     pub items: EnumMap<ItemSlot, Option<Item>>,
 }
 
+impl ItemAssignment {
+    /// The assigned items, if every slot is filled.
+    pub fn assigned_items(&self) -> Option<Vec<Item>> {
+        self.items.clone().into_iter().collect()
+    }
+}
+
 impl Default for ItemAssignment {
     fn default() -> Self {
         Self {
@@ -83,6 +91,13 @@ impl AssignItem {
 #[derive(Event, Deserialize, Serialize)]
 pub struct StartBuild(pub usize);
 
+/// Starts building the unit building of a fully assigned [`ItemAssignment`].
+#[derive(Event, Clone, Copy)]
+pub struct BuildAssignment {
+    pub player: Entity,
+    pub building: Entity,
+}
+
 #[derive(Resource, Default, Deref, DerefMut)]
 struct ActiveBuilding(HashMap<Entity, Entity>);
 
@@ -99,29 +114,40 @@ impl ActiveBuildingExt for ActiveBuilding {
 
 fn check_start_building(
     trigger: On<FromClient<StartBuild>>,
-    mut interactions: MessageWriter<InteractionTriggeredEvent>,
     assignment: Query<&ItemAssignment>,
     active: Res<ActiveBuilding>,
     client_player_map: Res<ClientPlayerMap>,
+    mut commands: Commands,
+) -> Result {
+    let player = *client_player_map.get_player(&trigger.client_id)?;
+    let building = *active.get_entity(&player)?;
+
+    if assignment.get(building)?.assigned_items().is_none() {
+        info!("Not all items assigned!");
+        return Ok(());
+    }
+
+    commands.trigger(BuildAssignment { player, building });
+
+    commands.server_trigger(ToClients {
+        mode: SendMode::Direct(trigger.client_id),
+        message: CloseBuildingDialog(0),
+    });
+    Ok(())
+}
+
+fn build_assignment(
+    trigger: On<BuildAssignment>,
+    mut interactions: MessageWriter<InteractionTriggeredEvent>,
+    assignment: Query<&ItemAssignment>,
     players: Query<&Player>,
     mut commands: Commands,
 ) -> Result {
-    let player_entity = *client_player_map.get_player(&trigger.client_id)?;
-    let player = players.get(player_entity)?;
-    let active_building = *active.get_entity(&player_entity)?;
-
-    let assignment = assignment.get(active_building)?;
-    let items: Vec<_> = match assignment
-        .items
-        .clone()
-        .into_iter()
-        .collect::<Option<Vec<Item>>>()
-    {
-        Some(v) => v,
-        None => {
-            info!("Not all items assigned!");
-            return Ok(());
-        }
+    let BuildAssignment { player, building } = *trigger;
+    let color = players.get(player)?.color;
+
+    let Some(items) = assignment.get(building)?.assigned_items() else {
+        return Ok(());
     };
 
     if let Some(weapon) = items.into_iter().find_map(|item| {
@@ -131,10 +157,10 @@ fn check_start_building(
             None
         }
     }) {
-        commands.entity(active_building).insert((
+        commands.entity(building).insert((
             Building {
                 building_type: BuildingType::Unit { weapon },
-                color: player.color,
+                color,
             },
             RespawnZone::default(),
         ));
@@ -143,15 +169,10 @@ fn check_start_building(
     }
 
     interactions.write(InteractionTriggeredEvent {
-        player: player_entity,
-        interactable: active_building,
+        player,
+        interactable: building,
         interaction: InteractionType::Building,
     });
-
-    commands.server_trigger(ToClients {
-        mode: SendMode::Direct(trigger.client_id),
-        message: CloseBuildingDialog(0),
-    });
     Ok(())
 }
 
@@ -5,12 +5,16 @@ use item_assignment::ItemAssignmentPlugins;
 use recruiting::{assign_offset, check_recruit, recruit_commander, recruit_units};
 use respawn::respawn_units;
 use siege_camp::siege_camp_lifetime;
+use siege_engine::SiegeEnginePlugin;
 
 use crate::{
     GameState, Owner,
     map::buildings::{BuildStatus, Building, HealthIndicator, respawn_timer},
     networking::Inventory,
-    server::players::interaction::Interactable,
+    server::{
+        players::interaction::Interactable,
+        stats::{GoldReason, GoldTransaction},
+    },
 };
 
 use super::players::interaction::{InteractionTriggeredEvent, InteractionType};
@@ -21,6 +25,7 @@ pub mod gold_farm;
 pub mod item_assignment;
 pub mod recruiting;
 pub mod siege_camp;
+pub mod siege_engine;
 
 #[derive(Clone)]
 pub struct BuildingEventInfo {
@@ -30,7 +35,7 @@ pub struct BuildingEventInfo {
 }
 
 #[derive(Message, Deref)]
-struct BuildingChangeStart(pub BuildingEventInfo);
+pub struct BuildingChangeStart(pub BuildingEventInfo);
 
 #[derive(Component)]
 struct BuildingConstructing {
@@ -45,7 +50,7 @@ pub struct BuildingsPlugins;
 
 impl Plugin for BuildingsPlugins {
     fn build(&self, app: &mut App) {
-        app.add_plugins(ItemAssignmentPlugins)
+        app.add_plugins((ItemAssignmentPlugins, SiegeEnginePlugin))
             .add_message::<BuildingChangeStart>()
             .add_message::<BuildingChangeEnd>();
 
@@ -137,6 +142,7 @@ fn start_construction(
     mut events: MessageReader<BuildingChangeStart>,
     mut inventory: Query<&mut Inventory>,
     mut building_query: Query<&mut Building>,
+    mut gold: MessageWriter<GoldTransaction>,
     mut commands: Commands,
 ) -> Result {
     for event in events.read() {
@@ -160,6 +166,11 @@ fn start_construction(
 
         let mut inventory = inventory.get_mut(event.player_entity)?;
         inventory.gold -= building.cost().gold;
+        gold.write(GoldTransaction::spent(
+            event.player_entity,
+            building.cost().gold,
+            GoldReason::Building,
+        ));
     }
     Ok(())
 }
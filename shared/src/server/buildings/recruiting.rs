@@ -16,7 +16,7 @@ use crate::{
     server::{
         ai::{FollowOffset, UnitBehaviour},
         entities::{
-            Damage, MeleeRange, ProjectileRange, Sight, Unit,
+            Accuracy, Ammunition, Armor, Damage, MeleeRange, ProjectileRange, Sight, Unit,
             commander::{
                 ArmyFlagAssignments, ArmyFormation, ArmyPosition, BASE_FORMATION_OFFSET,
                 BASE_FORMATION_WIDTH,
@@ -28,8 +28,9 @@ use crate::{
             interaction::{
                 Interactable, InteractableSound, InteractionTriggeredEvent, InteractionType,
             },
-            items::{CalculatedStats, Effect, Item, ItemType},
+            items::{CalculatedStats, Effect, Item, ItemType, WeaponType},
         },
+        stats::{GoldReason, GoldTransaction},
     },
 };
 
@@ -102,8 +103,8 @@ pub fn assign_offset(
 
 #[derive(Event, Deserialize, Serialize)]
 pub struct RecruitEvent {
-    player: Entity,
-    unit_type: UnitType,
+    pub player: Entity,
+    pub unit_type: UnitType,
     items: Option<Vec<Item>>,
     original_building: Entity,
 }
@@ -127,6 +128,7 @@ impl RecruitEvent {
 pub fn recruit_units(
     trigger: On<RecruitEvent>,
     mut player_query: Query<(&Transform, &mut Inventory, &Player, &GameSceneId)>,
+    mut gold: MessageWriter<GoldTransaction>,
     mut commands: Commands,
 ) -> Result {
     let RecruitEvent {
@@ -153,6 +155,11 @@ pub fn recruit_units(
 
     let cost = &unit_type.recruitment_cost();
     inventory.gold -= cost.gold;
+    gold.write(GoldTransaction::spent(
+        player,
+        cost.gold,
+        GoldReason::Recruitment,
+    ));
 
     let owner = Owner::Player(player);
     let flag_entity = commands
@@ -207,8 +214,18 @@ fn spawn_units(
 ) {
     let unit_amount = items.calculated(Effect::UnitAmount) as i32;
 
-    let (unit, health, speed, damage, melee_range, projectile_range, sight) =
-        unit_stats(unit_type, items, color);
+    let (
+        unit,
+        health,
+        speed,
+        damage,
+        melee_range,
+        projectile_range,
+        accuracy,
+        ammunition,
+        sight,
+        armor,
+    ) = unit_stats(unit_type, items, color);
 
     for _ in 1..=unit_amount {
         commands.spawn((
@@ -219,7 +236,10 @@ fn spawn_units(
             damage,
             melee_range,
             projectile_range,
+            accuracy,
+            ammunition,
             sight,
+            armor,
             owner,
             *game_scene_id,
             FlagAssignment(flag_entity),
@@ -239,7 +259,10 @@ pub fn unit_stats(
     Damage,
     MeleeRange,
     ProjectileRange,
+    Accuracy,
+    Ammunition,
     Sight,
+    Armor,
 ) {
     let time = 60. / items.calculated(Effect::AttackSpeed);
     let unit = Unit {
@@ -273,9 +296,32 @@ pub fn unit_stats(
     });
     let projectile_range = ProjectileRange(projectile_range);
 
+    let accuracy = items.calculated(|item: &Item| {
+        let ItemType::Weapon(weapon) = item.item_type else {
+            return None;
+        };
+        Some(Effect::Accuracy(weapon))
+    });
+    let accuracy = Accuracy(accuracy / 100.);
+
+    let ammunition = items
+        .iter()
+        .find_map(|item| match item.item_type {
+            ItemType::Weapon(WeaponType::Projectile(weapon)) => {
+                Some(Ammunition(weapon.projectile()))
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+
     let sight = items.calculated(Effect::Sight);
     let sight = Sight(sight);
 
+    let armor = Armor {
+        physical: items.calculated(Effect::Armor),
+        magical: items.calculated(Effect::MagicResist),
+    };
+
     (
         unit,
         health,
@@ -283,13 +329,17 @@ pub fn unit_stats(
         damage,
         melee_range,
         projectile_range,
+        accuracy,
+        ammunition,
         sight,
+        armor,
     )
 }
 
 pub fn recruit_commander(
     trigger: On<RecruitEvent>,
     mut player_query: Query<(&Transform, &mut Inventory, &Player, &GameSceneId)>,
+    mut gold: MessageWriter<GoldTransaction>,
     mut commands: Commands,
 ) -> Result {
     let RecruitEvent {
@@ -310,6 +360,11 @@ pub fn recruit_commander(
 
     let cost = &unit_type.recruitment_cost();
     inventory.gold -= cost.gold;
+    gold.write(GoldTransaction::spent(
+        player,
+        cost.gold,
+        GoldReason::Recruitment,
+    ));
 
     let owner = Owner::Player(player);
     let flag_entity = commands
@@ -12,7 +12,10 @@ use crate::{
     server::{
         ai::UnitBehaviour,
         entities::commander::ArmyFlagAssignments,
+        physics::spatial::SpatialIndex,
         players::items::{CalculatedStats, Effect, Item},
+        players::team::Factions,
+        stats::{GoldReason, GoldTransaction},
     },
 };
 
@@ -39,6 +42,9 @@ pub fn respawn_units(
     commander: Query<&ArmyFlagAssignments>,
     flag_query: Query<(&Flag, Option<&FlagUnits>)>,
     mut inventory_query: Query<&mut Inventory>,
+    mut gold: MessageWriter<GoldTransaction>,
+    factions: Factions,
+    index: Res<SpatialIndex>,
     mut commands: Commands,
 ) -> Result {
     for (
@@ -53,21 +59,28 @@ pub fn respawn_units(
     {
         let flag_bounds = flag_collider.at(flag_transform);
 
-        let matching_building =
-            respawn_zones
-                .iter_mut()
-                .find(|(recruit, transform, collider, owner)| {
-                    if !recruit.respawn_timer_finished() {
-                        return false;
-                    }
-                    if !flag_owner.is_same_faction(owner) {
-                        return false;
-                    }
-                    let building_bounds = collider.at(transform);
-                    flag_bounds.intersects(&building_bounds)
-                });
+        let matching_building = index
+            .overlapping(*flag_game_scene_id, &flag_bounds)
+            .into_iter()
+            .find(|candidate| {
+                let Ok((recruit, transform, collider, owner)) = respawn_zones.get(*candidate)
+                else {
+                    return false;
+                };
+                if !recruit.respawn_timer_finished() {
+                    return false;
+                }
+                // Allies share their siege camps and respawn zones.
+                if !factions.is_allied(flag_owner, owner) {
+                    return false;
+                }
+                let building_bounds = collider.at(transform);
+                flag_bounds.intersects(&building_bounds)
+            });
 
-        let Some((mut recruit_component, respawn_transform, ..)) = matching_building else {
+        let Some(Ok((mut recruit_component, respawn_transform, ..))) =
+            matching_building.map(|building| respawn_zones.get_mut(building))
+        else {
             continue;
         };
 
@@ -80,7 +93,7 @@ pub fn respawn_units(
 
         match original_building.get(flag.original_building) {
             Ok((building, assignment)) => {
-                respawn_for_flag(
+                let respawned = respawn_for_flag(
                     commands.reborrow(),
                     flag_entity,
                     flag,
@@ -92,6 +105,13 @@ pub fn respawn_units(
                     &mut inventory,
                     flag_game_scene_id,
                 );
+                if respawned {
+                    gold.write(GoldTransaction::spent(
+                        player,
+                        RESPAWN_COST_GOLD,
+                        GoldReason::Respawn,
+                    ));
+                }
             }
 
             Err(_) => {
@@ -109,7 +129,7 @@ pub fn respawn_units(
                     let (flag, maybe_flag_units) = flag_query.get(*flag_entity)?;
                     let (building, assignment) = original_building.get(flag.original_building)?;
 
-                    respawn_for_flag(
+                    let respawned = respawn_for_flag(
                         commands.reborrow(),
                         *flag_entity,
                         flag,
@@ -121,6 +141,13 @@ pub fn respawn_units(
                         &mut inventory,
                         flag_game_scene_id,
                     );
+                    if respawned {
+                        gold.write(GoldTransaction::spent(
+                            player,
+                            RESPAWN_COST_GOLD,
+                            GoldReason::Respawn,
+                        ));
+                    }
                 }
             }
         };
@@ -128,6 +155,7 @@ pub fn respawn_units(
     Ok(())
 }
 
+/// Respawns one missing unit of the flag, returns whether it was paid for and spawned.
 fn respawn_for_flag(
     mut commands: Commands,
     flag_entity: Entity,
@@ -139,7 +167,7 @@ fn respawn_for_flag(
     assignment: &ItemAssignment,
     inventory: &mut Inventory,
     game_scene_id: &GameSceneId,
-) {
+) -> bool {
     let items: Vec<Item> = assignment.items.clone().into_iter().flatten().collect();
 
     let num_alive = match maybe_flag_units {
@@ -148,28 +176,42 @@ fn respawn_for_flag(
     };
     let max_allowed = items.calculated(Effect::UnitAmount) as i32;
 
-    if num_alive < max_allowed {
-        if inventory.gold < RESPAWN_COST_GOLD {
-            return;
-        }
-        inventory.gold -= RESPAWN_COST_GOLD;
-
-        let (unit, health, speed, damage, melee_range, projectile_range, sight) =
-            unit_stats(building.unit_type().unwrap(), &items, flag.color);
-
-        commands.spawn((
-            respawn_transform.translation.with_layer(Layers::Unit),
-            unit.clone(),
-            health,
-            speed,
-            damage,
-            melee_range,
-            projectile_range,
-            sight,
-            *flag_owner,
-            *game_scene_id,
-            FlagAssignment(flag_entity),
-            UnitBehaviour::default(),
-        ));
+    if num_alive >= max_allowed || inventory.gold < RESPAWN_COST_GOLD {
+        return false;
     }
+
+    inventory.gold -= RESPAWN_COST_GOLD;
+
+    let (
+        unit,
+        health,
+        speed,
+        damage,
+        melee_range,
+        projectile_range,
+        accuracy,
+        ammunition,
+        sight,
+        armor,
+    ) = unit_stats(building.unit_type().unwrap(), &items, flag.color);
+
+    commands.spawn((
+        respawn_transform.translation.with_layer(Layers::Unit),
+        unit.clone(),
+        health,
+        speed,
+        damage,
+        melee_range,
+        projectile_range,
+        accuracy,
+        ammunition,
+        sight,
+        armor,
+        *flag_owner,
+        *game_scene_id,
+        FlagAssignment(flag_entity),
+        UnitBehaviour::default(),
+    ));
+
+    true
 }
@@ -0,0 +1,379 @@
+use bevy::prelude::*;
+
+use std::time::Duration;
+
+use bevy::sprite::Anchor;
+use bevy_replicon::prelude::{Replicated, SendMode, ServerTriggerExt, ToClients};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BoxCollider, GameSceneId, Hitby, Owner, Vec3LayerExt,
+    map::{
+        Layers,
+        buildings::{BuildStatus, Building, Cost},
+    },
+    networking::Inventory,
+    server::{
+        entities::{
+            Armor, Damage, Unit,
+            health::{DamageType, Health, TakeDamage},
+        },
+        physics::{
+            ballistics::{Ballistics, launch_velocity, scatter},
+            movement::Velocity,
+            projectile::{ProjectileHits, ProjectileType, Shooter},
+            spatial::SpatialIndex,
+        },
+        players::{
+            interaction::{
+                Interactable, InteractableSound, InteractionTriggeredEvent, InteractionType,
+            },
+            team::Factions,
+        },
+        stats::{GoldReason, GoldTransaction},
+    },
+};
+
+use super::siege_camp::SiegeCamp;
+
+/// Allied units have to stand this close to a siege engine to crew it.
+const CREW_RANGE: f32 = 60.;
+
+/// Distance from its camp a blueprint is offered at.
+const BLUEPRINT_OFFSET: f32 = 50.;
+
+/// Gap to a building at which the ram strikes it.
+const RAM_REACH: f32 = 5.;
+
+const RAM_SPEED: f32 = 30.;
+
+/// Catapults aim poorly, from 0 to 1 like [`crate::server::entities::Accuracy`].
+const CATAPULT_ACCURACY: f32 = 0.4;
+
+/// Heavy engines a siege camp can build, strong against buildings and weak against units.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[require(
+    Replicated,
+    Transform,
+    BoxCollider,
+    Velocity,
+    Sprite,
+    Anchor::BOTTOM_CENTER
+)]
+pub enum SiegeEngine {
+    /// Hurls boulders at buildings from afar.
+    Catapult,
+    /// Rolls up to a building and batters it.
+    Ram,
+}
+
+impl SiegeEngine {
+    pub fn cost(&self) -> Cost {
+        let gold = match self {
+            SiegeEngine::Catapult => 300,
+            SiegeEngine::Ram => 200,
+        };
+        Cost { gold }
+    }
+
+    /// Allied units needed nearby to operate the engine.
+    pub fn crew(&self) -> usize {
+        match self {
+            SiegeEngine::Catapult => 2,
+            SiegeEngine::Ram => 3,
+        }
+    }
+
+    pub fn health(&self) -> Health {
+        let hitpoints = match self {
+            SiegeEngine::Catapult => 250.,
+            SiegeEngine::Ram => 400.,
+        };
+        Health { hitpoints }
+    }
+
+    pub fn damage(&self) -> f32 {
+        match self {
+            SiegeEngine::Catapult => 40.,
+            SiegeEngine::Ram => 120.,
+        }
+    }
+
+    /// Seconds between two shots or strikes.
+    pub fn reload(&self) -> f32 {
+        match self {
+            SiegeEngine::Catapult => 6.,
+            SiegeEngine::Ram => 3.,
+        }
+    }
+
+    /// Distance at which the engine picks a building to attack.
+    pub fn range(&self) -> f32 {
+        match self {
+            SiegeEngine::Catapult => 420.,
+            SiegeEngine::Ram => 300.,
+        }
+    }
+
+    pub fn collider(&self) -> BoxCollider {
+        match self {
+            SiegeEngine::Catapult => BoxCollider {
+                dimension: Vec2::new(32., 24.),
+                offset: Some(Vec2::new(0., 12.)),
+            },
+            SiegeEngine::Ram => BoxCollider {
+                dimension: Vec2::new(44., 20.),
+                offset: Some(Vec2::new(0., 10.)),
+            },
+        }
+    }
+}
+
+/// A siege engine offered by a camp that nobody paid for yet.
+#[derive(Component, Deref)]
+#[relationship(relationship_target = Blueprints)]
+struct BlueprintOf(Entity);
+
+/// Unbuilt engines of a camp, they disappear with it.
+#[derive(Component, Deref)]
+#[relationship_target(relationship = BlueprintOf, linked_spawn)]
+struct Blueprints(Vec<Entity>);
+
+#[derive(Component, Deref, DerefMut)]
+struct SiegeReload(Timer);
+
+pub struct SiegeEnginePlugin;
+
+impl Plugin for SiegeEnginePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(offer_blueprints).add_systems(
+            FixedUpdate,
+            (
+                build_siege_engine.run_if(on_message::<InteractionTriggeredEvent>),
+                operate_siege_engines,
+                destroy_siege_engines,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn offer_blueprints(
+    trigger: On<Add, SiegeCamp>,
+    camps: Query<(&Transform, &Owner, &GameSceneId)>,
+    mut commands: Commands,
+) -> Result {
+    let camp = trigger.entity;
+    let (transform, owner, game_scene_id) = camps.get(camp)?;
+    let player = owner.entity()?;
+
+    for (engine, offset) in [
+        (SiegeEngine::Catapult, -BLUEPRINT_OFFSET),
+        (SiegeEngine::Ram, BLUEPRINT_OFFSET),
+    ] {
+        commands.spawn((
+            engine,
+            engine.collider(),
+            transform
+                .translation
+                .offset_x(offset)
+                .with_layer(Layers::Building),
+            *owner,
+            *game_scene_id,
+            BlueprintOf(camp),
+            Interactable {
+                kind: InteractionType::SiegeEngine,
+                restricted_to: Some(player),
+            },
+        ));
+    }
+    Ok(())
+}
+
+fn build_siege_engine(
+    mut interactions: MessageReader<InteractionTriggeredEvent>,
+    mut players: Query<&mut Inventory>,
+    blueprints: Query<(&SiegeEngine, &Transform), With<BlueprintOf>>,
+    mut gold: MessageWriter<GoldTransaction>,
+    mut commands: Commands,
+) -> Result {
+    for event in interactions.read() {
+        let InteractionType::SiegeEngine = &event.interaction else {
+            continue;
+        };
+        let mut inventory = players.get_mut(event.player)?;
+        let Ok((engine, transform)) = blueprints.get(event.interactable) else {
+            continue;
+        };
+
+        let cost = engine.cost().gold;
+        if inventory.gold < cost {
+            continue;
+        }
+        inventory.gold -= cost;
+        gold.write(GoldTransaction::spent(
+            event.player,
+            cost,
+            GoldReason::Building,
+        ));
+
+        let mut reload = Timer::from_seconds(engine.reload(), TimerMode::Once);
+        reload.tick(Duration::MAX);
+        commands
+            .entity(event.interactable)
+            .remove::<(BlueprintOf, Interactable)>()
+            .insert((engine.health(), Armor::default(), SiegeReload(reload)));
+
+        commands.server_trigger(ToClients {
+            mode: SendMode::Broadcast,
+            message: InteractableSound {
+                kind: InteractionType::SiegeEngine,
+                spatial_position: transform.translation,
+            },
+        });
+    }
+    Ok(())
+}
+
+fn operate_siege_engines(
+    mut engines: Query<
+        (
+            Entity,
+            &SiegeEngine,
+            &Transform,
+            &BoxCollider,
+            &mut Velocity,
+            &Owner,
+            &GameSceneId,
+            &mut SiegeReload,
+        ),
+        With<Health>,
+    >,
+    crew: Query<(&Transform, &Owner), (With<Unit>, With<Health>)>,
+    buildings: Query<(Entity, &Transform, &BoxCollider, &Owner, &BuildStatus), With<Building>>,
+    factions: Factions,
+    index: Res<SpatialIndex>,
+    ballistics: Res<Ballistics>,
+    time: Res<Time>,
+    mut attack_events: MessageWriter<TakeDamage>,
+    mut commands: Commands,
+) {
+    for (entity, engine, transform, collider, mut velocity, owner, game_scene_id, mut reload) in
+        engines.iter_mut()
+    {
+        reload.tick(time.delta());
+        velocity.0.x = 0.;
+
+        let x = transform.translation.x;
+        let crew_size = index
+            .near(*game_scene_id, x, CREW_RANGE)
+            .into_iter()
+            .filter_map(|candidate| crew.get(candidate).ok())
+            .filter(|(crew_transform, crew_owner)| {
+                factions.is_allied(owner, crew_owner)
+                    && (crew_transform.translation.x - x).abs() <= CREW_RANGE
+            })
+            .count();
+        if crew_size < engine.crew() {
+            continue;
+        }
+
+        let target = index
+            .near(*game_scene_id, x, engine.range())
+            .into_iter()
+            .filter_map(|candidate| buildings.get(candidate).ok())
+            .filter(|(.., building_owner, status)| {
+                matches!(status, BuildStatus::Built { .. })
+                    && factions.is_enemy(owner, building_owner)
+            })
+            .map(|(building, building_transform, building_collider, ..)| {
+                let delta_x = building_transform.translation.x - x;
+                let gap = delta_x.abs() - building_collider.half_size().x - collider.half_size().x;
+                (
+                    building,
+                    building_transform,
+                    building_collider,
+                    delta_x,
+                    gap,
+                )
+            })
+            .filter(|(.., gap)| *gap <= engine.range())
+            .min_by(|(.., a), (.., b)| a.total_cmp(b));
+        let Some((building, building_transform, building_collider, delta_x, gap)) = target else {
+            continue;
+        };
+
+        match engine {
+            SiegeEngine::Ram => {
+                if gap > RAM_REACH {
+                    velocity.0.x = delta_x.signum() * RAM_SPEED;
+                    continue;
+                }
+                if !reload.is_finished() {
+                    continue;
+                }
+
+                attack_events.write(TakeDamage {
+                    target_entity: building,
+                    damage: engine.damage(),
+                    damage_type: DamageType::Physical,
+                    direction: delta_x.into(),
+                    by: Hitby::Melee,
+                    attacker: Some(entity),
+                });
+            }
+            SiegeEngine::Catapult => {
+                if !reload.is_finished() {
+                    continue;
+                }
+
+                let origin = transform.translation.offset_y(collider.dimension.y);
+                let target_pos = building_transform.translation.truncate()
+                    + Vec2::Y * building_collider.half_size().y;
+                let delta = target_pos - origin.truncate();
+                let distance = delta.length();
+                let range = engine.range();
+
+                let Some(launch) = launch_velocity(
+                    delta,
+                    ballistics.launch_speed(range),
+                    ballistics.arc(distance, range),
+                ) else {
+                    continue;
+                };
+                let launch = scatter(
+                    launch,
+                    ballistics.spread(distance, range, CATAPULT_ACCURACY),
+                );
+
+                let projectile_type = ProjectileType::Boulder;
+                commands.spawn((
+                    Transform {
+                        translation: origin.with_z(Layers::Projectile.as_f32()),
+                        rotation: Quat::from_rotation_z(launch.to_angle()),
+                        ..default()
+                    },
+                    *owner,
+                    projectile_type,
+                    Velocity(launch),
+                    Damage(engine.damage()),
+                    Shooter(entity),
+                    ProjectileHits::new(projectile_type.pierce()),
+                    *game_scene_id,
+                ));
+            }
+        }
+        reload.reset();
+    }
+}
+
+fn destroy_siege_engines(
+    engines: Query<(Entity, &Health), With<SiegeEngine>>,
+    mut commands: Commands,
+) {
+    for (entity, health) in engines.iter() {
+        if health.hitpoints <= 0. {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use bevy::{
+    ecs::{system::In, world::World},
+    remote::{BrpError, BrpResult},
+};
+use bevy_replicon::prelude::{ClientId, FromClient, SendMode, ToClients};
+use console_protocol::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{ClientPlayerMap, Player};
+
+use super::{
+    invalid_params, list_players, loot_tables, match_stats, spawn_full_commander,
+    spawn_random_items, spawn_unit_and_bandits, spawn_unit_handler,
+};
+
+pub struct InGameConsolePlugin;
+
+impl Plugin for InGameConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingConsoleRequests>()
+            .add_observer(queue_console_request)
+            .add_systems(Update, run_console_requests);
+    }
+}
+
+/// Allows players to run console commands on this host from the in-game console.
+#[derive(Resource)]
+pub struct CheatsEnabled;
+
+/// A command typed into the in-game console, executed like the BRP method of the same name.
+#[derive(Event, Clone, Serialize, Deserialize)]
+pub struct ConsoleRequest {
+    pub method: String,
+    /// JSON encoded parameters of the BRP method.
+    pub params: Option<String>,
+}
+
+/// The result of a [`ConsoleRequest`], formatted for display.
+#[derive(Message, Serialize, Deserialize)]
+pub struct ConsoleOutput {
+    pub text: String,
+    pub error: bool,
+}
+
+#[derive(Resource, Default)]
+struct PendingConsoleRequests(Vec<(ClientId, ConsoleRequest)>);
+
+fn queue_console_request(
+    trigger: On<FromClient<ConsoleRequest>>,
+    mut pending: ResMut<PendingConsoleRequests>,
+) {
+    pending
+        .0
+        .push((trigger.client_id, (**trigger.event()).clone()));
+}
+
+fn run_console_requests(world: &mut World) {
+    let requests = std::mem::take(&mut world.resource_mut::<PendingConsoleRequests>().0);
+
+    for (client_id, request) in requests {
+        let method = request.method.clone();
+        let output = match execute_request(world, client_id, request) {
+            Ok(result) => ConsoleOutput {
+                text: format_result(&method, &result),
+                error: false,
+            },
+            Err(error) => ConsoleOutput {
+                text: error.message,
+                error: true,
+            },
+        };
+
+        world.write_message(ToClients {
+            mode: SendMode::Direct(client_id),
+            message: output,
+        });
+    }
+}
+
+fn execute_request(world: &mut World, client_id: ClientId, request: ConsoleRequest) -> BrpResult {
+    if !world.contains_resource::<CheatsEnabled>() {
+        return Err(BrpError {
+            code: error_codes::CHEATS_DISABLED,
+            message: String::from("cheats are disabled on this host"),
+            data: None,
+        });
+    }
+
+    let mut params = request
+        .params
+        .map(|params| serde_json::from_str::<Value>(&params))
+        .transpose()
+        .map_err(|e| invalid_params(format!("invalid console parameters: {e}")))?;
+
+    // `local` means the host for BRP requests, but the sender for the in-game console.
+    if let Some(player) = params.as_mut().and_then(|params| params.get_mut("player"))
+        && let Ok(PlayerSelector::Local) = serde_json::from_value(player.clone())
+    {
+        let id = world
+            .resource::<ClientPlayerMap>()
+            .get(&client_id)
+            .and_then(|entity| world.get::<Player>(*entity))
+            .map(|player| player.id)
+            .ok_or_else(|| invalid_params("no player for this client"))?;
+        *player = serde_json::to_value(PlayerSelector::Id(id)).map_err(BrpError::internal)?;
+    }
+
+    match request.method.as_str() {
+        BRP_LIST_PLAYERS => list_players(In(params), world),
+        BRP_MATCH_STATS => match_stats(In(params), world),
+        BRP_LOOT_TABLES => loot_tables(In(params), world),
+        BRP_SPAWN_UNIT => spawn_unit_handler(In(params), world),
+        BRP_SPAWN_RANDOM_ITEM => spawn_random_items(In(params), world),
+        BRP_SPAWN_FULL_COMMANDER => spawn_full_commander(In(params), world),
+        BRP_SPAWN_UNIT_AND_BANDITS => spawn_unit_and_bandits(In(params), world),
+        other => Err(BrpError {
+            code: bevy::remote::error_codes::METHOD_NOT_FOUND,
+            message: format!("unknown console method `{other}`"),
+            data: None,
+        }),
+    }
+}
@@ -5,11 +5,12 @@ use bevy::{
     ecs::{entity::Entity, system::In, world::World},
     remote::{BrpError, BrpResult, RemotePlugin, http::RemoteHttpPlugin},
 };
+use bevy_replicon::prelude::ClientId;
 use console_protocol::*;
 use serde_json::{Value, json};
 
 use crate::{
-    ClientPlayerMap, Owner, Player, PlayerColor, Vec3LayerExt,
+    ClientPlayerMap, Disconnected, Owner, Player, PlayerColor, Vec3LayerExt,
     enum_map::{EnumIter, EnumMap},
     map::{
         Layers,
@@ -26,6 +27,9 @@ use crate::{
 };
 use crate::GameSceneId;
 
+use in_game::InGameConsolePlugin;
+use watch::{WatchPlugin, watch_events};
+
 use super::{
     ai::{FollowOffset, UnitBehaviour},
     buildings::{
@@ -46,76 +50,227 @@ use super::{
     players::{
         interaction::{Interactable, InteractionType},
         items::{Item, ItemType, MeleeWeapon, ProjectileWeapon, Rarity, WeaponType},
+        loot::{DropTable, LootTables},
     },
+    stats::MatchStats,
 };
 
+pub mod in_game;
+pub mod watch;
+
 pub struct ConsolePlugin;
 
 impl Plugin for ConsolePlugin {
     fn build(&self, app: &mut bevy::app::App) {
         app.add_plugins((
+            WatchPlugin,
+            InGameConsolePlugin,
             RemotePlugin::default()
+                .with_method(BRP_LIST_PLAYERS, list_players)
+                .with_method(BRP_MATCH_STATS, match_stats)
+                .with_method(BRP_LOOT_TABLES, loot_tables)
                 .with_method(BRP_SPAWN_UNIT, spawn_unit_handler)
                 .with_method(BRP_SPAWN_RANDOM_ITEM, spawn_random_items)
                 .with_method(BRP_SPAWN_FULL_COMMANDER, spawn_full_commander)
-                .with_method(BRP_SPAWN_UNIT_AND_BANDITS, spawn_unit_and_bandits),
+                .with_method(BRP_SPAWN_UNIT_AND_BANDITS, spawn_unit_and_bandits)
+                .with_watching_method(BRP_WATCH_EVENTS, watch_events),
             RemoteHttpPlugin::default(),
         ));
     }
 }
 
 trait PlayerCommand {
-    fn player(&self) -> u8;
+    fn player(&self) -> &PlayerSelector;
 
     fn player_entity(&self, world: &mut World) -> BrpResult<Entity> {
-        let client_player_map = world
-            .get_resource::<ClientPlayerMap>()
-            .ok_or_else(|| BrpError::internal("Missing ClientPlayerMap resource"))?;
-        let (_, player) = client_player_map
-            .iter()
-            .nth(self.player() as usize)
-            .ok_or_else(|| BrpError::internal("Player index out of bounds"))?;
-        let entity = player;
-
-        Ok(*entity)
+        resolve_player(world, self.player())
     }
 }
 
 impl PlayerCommand for BrpSpawnItems {
-    fn player(&self) -> u8 {
-        self.player
+    fn player(&self) -> &PlayerSelector {
+        &self.player
     }
 }
 impl PlayerCommand for BrpSpawnUnit {
-    fn player(&self) -> u8 {
-        self.player
+    fn player(&self) -> &PlayerSelector {
+        &self.player
     }
 }
 
 impl PlayerCommand for BrpSpawnFullCommander {
-    fn player(&self) -> u8 {
-        self.player
+    fn player(&self) -> &PlayerSelector {
+        &self.player
     }
 }
 
 impl PlayerCommand for BrpSpawnUnitAndBandits {
-    fn player(&self) -> u8 {
-        self.player
+    fn player(&self) -> &PlayerSelector {
+        &self.player
+    }
+}
+
+fn resolve_player(world: &mut World, selector: &PlayerSelector) -> BrpResult<Entity> {
+    let matches: Vec<(Entity, u64)> = match selector {
+        PlayerSelector::Local => {
+            let client_player_map = world
+                .get_resource::<ClientPlayerMap>()
+                .ok_or_else(|| BrpError::resource_not_present("ClientPlayerMap"))?;
+            return client_player_map
+                .get(&ClientId::Server)
+                .copied()
+                .ok_or_else(|| player_not_found(selector));
+        }
+        PlayerSelector::Id(id) => {
+            let mut query = world.query::<(Entity, &Player)>();
+            query
+                .iter(world)
+                .filter(|(_, player)| player.id == *id)
+                .map(|(entity, player)| (entity, player.id))
+                .collect()
+        }
+        PlayerSelector::Color(name) => {
+            let color = PlayerColor::all_variants()
+                .iter()
+                .find(|color| format!("{color:?}").eq_ignore_ascii_case(name))
+                .ok_or_else(|| invalid_params(format!("unknown player color `{name}`")))?;
+
+            let mut query = world.query::<(Entity, &Player)>();
+            query
+                .iter(world)
+                .filter(|(_, player)| player.color == *color)
+                .map(|(entity, player)| (entity, player.id))
+                .collect()
+        }
+    };
+
+    match matches.as_slice() {
+        [] => Err(player_not_found(selector)),
+        [(entity, _)] => Ok(*entity),
+        _ => {
+            let ids: Vec<u64> = matches.iter().map(|(_, id)| *id).collect();
+            Err(BrpError {
+                code: error_codes::PLAYER_AMBIGUOUS,
+                message: format!("player selector `{selector}` matches multiple players"),
+                data: Some(json!({ "ids": ids })),
+            })
+        }
+    }
+}
+
+fn player_not_found(selector: &PlayerSelector) -> BrpError {
+    BrpError {
+        code: error_codes::PLAYER_NOT_FOUND,
+        message: format!("no player matches selector `{selector}`"),
+        data: None,
+    }
+}
+
+fn invalid_params(message: impl Into<String>) -> BrpError {
+    BrpError {
+        code: bevy::remote::error_codes::INVALID_PARAMS,
+        message: message.into(),
+        data: None,
+    }
+}
+
+fn list_players(In(_): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let local = world
+        .get_resource::<ClientPlayerMap>()
+        .and_then(|client_player_map| client_player_map.get(&ClientId::Server).copied());
+
+    let mut query = world.query::<(Entity, &Player, Has<Disconnected>)>();
+    let mut players: Vec<BrpPlayerInfo> = query
+        .iter(world)
+        .map(|(entity, player, disconnected)| BrpPlayerInfo {
+            id: player.id,
+            color: format!("{:?}", player.color).to_lowercase(),
+            local: local.is_some_and(|local| local == entity),
+            connected: !disconnected,
+        })
+        .collect();
+    players.sort_by_key(|player| player.id);
+
+    serde_json::to_value(players).map_err(BrpError::internal)
+}
+
+fn match_stats(In(_): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let summary = world.resource::<MatchStats>().summary();
+
+    serde_json::to_value(summary).map_err(BrpError::internal)
+}
+
+fn loot_tables(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let changes: BrpLootTables = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| invalid_params(format!("invalid loot parameters: {e}")))?
+        .unwrap_or_default();
+
+    let bandit = changes.bandit.map(drop_table).transpose()?;
+    let building = changes.building.map(drop_table).transpose()?;
+    if let Some(share) = changes.king_gold_share
+        && !(0. ..=1.).contains(&share)
+    {
+        return Err(invalid_params("king gold share must be between 0 and 1"));
+    }
+
+    let mut loot = world.resource_mut::<LootTables>();
+    if let Some(bandit) = bandit {
+        loot.bandit = bandit;
+    }
+    if let Some(building) = building {
+        loot.building = building;
+    }
+    if let Some(share) = changes.king_gold_share {
+        loot.king_gold_share = share;
+    }
+
+    let tables = BrpLootTables {
+        bandit: Some(brp_drop_table(&loot.bandit)),
+        building: Some(brp_drop_table(&loot.building)),
+        king_gold_share: Some(loot.king_gold_share),
+    };
+    serde_json::to_value(tables).map_err(BrpError::internal)
+}
+
+fn drop_table(table: BrpDropTable) -> BrpResult<DropTable> {
+    let chances = 0. ..=1.;
+    if !chances.contains(&table.gold_chance) || !chances.contains(&table.item_chance) {
+        return Err(invalid_params("drop chances must be between 0 and 1"));
+    }
+    if table.gold_min > table.gold_max {
+        return Err(invalid_params("minimum gold must not exceed the maximum"));
+    }
+
+    Ok(DropTable {
+        gold_chance: table.gold_chance,
+        gold: table.gold_min..=table.gold_max,
+        item_chance: table.item_chance,
+    })
+}
+
+fn brp_drop_table(table: &DropTable) -> BrpDropTable {
+    BrpDropTable {
+        gold_chance: table.gold_chance,
+        gold_min: *table.gold.start(),
+        gold_max: *table.gold.end(),
+        item_chance: table.item_chance,
     }
 }
 
 fn spawn_unit_handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult<Value> {
-    let value = params.ok_or_else(|| BrpError::internal("spawn-units requires parameters"))?;
+    let value = params.ok_or_else(|| invalid_params("spawn-units requires parameters"))?;
 
     let unit_req: BrpSpawnUnit = serde_json::from_value(value)
-        .map_err(|e| BrpError::internal(format!("invalid spawn parameters: {e}")))?;
+        .map_err(|e| invalid_params(format!("invalid spawn parameters: {e}")))?;
 
     let unit_type = match unit_req.unit.as_str() {
         "archer" => UnitType::Archer,
         "pikemen" => UnitType::Pikeman,
         "shield" => UnitType::Shieldwarrior,
         other => {
-            return Err(BrpError::internal(format!("unknown unit type `{other}`")));
+            return Err(invalid_params(format!("unknown unit type `{other}`")));
         }
     };
 
@@ -208,11 +363,10 @@ fn spawn_random_items(In(params): In<Option<serde_json::Value>>, world: &mut Wor
 }
 
 fn spawn_full_commander(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
-    let value =
-        params.ok_or_else(|| BrpError::internal("spawn-full-commander requires parameters"))?;
+    let value = params.ok_or_else(|| invalid_params("spawn-full-commander requires parameters"))?;
 
     let brp: BrpSpawnFullCommander = serde_json::from_value(value)
-        .map_err(|e| BrpError::internal(format!("invalid commander parameters: {e}")))?;
+        .map_err(|e| invalid_params(format!("invalid commander parameters: {e}")))?;
     let player = brp.player_entity(world)?;
     let (player_component, game_scene_id) = world
         .entity(player)
@@ -0,0 +1,276 @@
+use bevy::prelude::*;
+
+use bevy::{
+    ecs::{system::In, world::World},
+    remote::{BrpError, BrpResult},
+};
+use console_protocol::*;
+use serde_json::Value;
+
+use crate::{
+    GameSceneId, Owner, Player,
+    map::buildings::{BuildStatus, Building, BuildingType},
+    server::{
+        buildings::{BuildingChangeEnd, BuildingChangeStart, recruiting::RecruitEvent},
+        entities::{
+            Unit,
+            health::{DamageApplied, UnitDied},
+        },
+        players::flag::{DropFlagEvent, PickFlagEvent},
+    },
+};
+
+use super::{invalid_params, player_not_found, resolve_player};
+
+pub struct WatchPlugin;
+
+impl Plugin for WatchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WatchedEvents>()
+            .add_observer(watch_unit_deaths)
+            .add_observer(watch_recruits)
+            .add_systems(First, clear_watched_events)
+            .add_systems(
+                FixedUpdate,
+                (watch_damage, watch_buildings, watch_flags, watch_defeats),
+            );
+    }
+}
+
+/// Gameplay events of the current frame, streamed to `ppc watch`.
+#[derive(Resource, Default)]
+pub struct WatchedEvents(Vec<BrpGameEvent>);
+
+impl WatchedEvents {
+    pub fn push(&mut self, scene: Option<&GameSceneId>, players: Vec<u64>, kind: BrpGameEventKind) {
+        self.0.push(BrpGameEvent {
+            scene: scene.map(GameSceneId::index),
+            players,
+            kind,
+        });
+    }
+}
+
+type Describe<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static Player>,
+        Option<&'static Unit>,
+        Option<&'static Building>,
+    ),
+>;
+
+fn describe(entity: Entity, query: &Describe) -> String {
+    match query.get(entity) {
+        Ok((Some(player), _, _)) => format!("player {}", player.id),
+        Ok((_, Some(unit), _)) => format!("{:?} {entity}", unit.unit_type),
+        Ok((_, _, Some(building))) => format!("{:?} {entity}", building.building_type),
+        _ => format!("{entity}"),
+    }
+}
+
+fn owner_ids(owner: Option<&Owner>, players: &Query<&Player>) -> Vec<u64> {
+    owner
+        .and_then(|owner| owner.entity().ok())
+        .and_then(|entity| players.get(entity).ok())
+        .map(|player| vec![player.id])
+        .unwrap_or_default()
+}
+
+fn player_ids(entity: Entity, players: &Query<&Player>) -> Vec<u64> {
+    players
+        .get(entity)
+        .map(|player| vec![player.id])
+        .unwrap_or_default()
+}
+
+fn clear_watched_events(mut watched: ResMut<WatchedEvents>) {
+    watched.0.clear();
+}
+
+fn watch_damage(
+    mut damage: MessageReader<DamageApplied>,
+    mut watched: ResMut<WatchedEvents>,
+    targets: Query<(Option<&GameSceneId>, Option<&Owner>)>,
+    players: Query<&Player>,
+    describe_query: Describe,
+) {
+    for event in damage.read() {
+        let Ok((scene, owner)) = targets.get(event.target_entity) else {
+            continue;
+        };
+
+        let mut involved = owner_ids(owner, &players);
+        if involved.is_empty() {
+            involved = player_ids(event.target_entity, &players);
+        }
+
+        watched.push(
+            scene,
+            involved,
+            BrpGameEventKind::Damage {
+                target: describe(event.target_entity, &describe_query),
+                damage: event.damage,
+                by: format!("{:?}", event.by),
+            },
+        );
+    }
+}
+
+fn watch_unit_deaths(
+    trigger: On<UnitDied>,
+    mut watched: ResMut<WatchedEvents>,
+    scenes: Query<&GameSceneId>,
+    players: Query<&Player>,
+) {
+    let death = trigger.event();
+
+    watched.push(
+        scenes.get(death.entity).ok(),
+        owner_ids(Some(&death.owner), &players),
+        BrpGameEventKind::UnitDeath {
+            unit: format!("{:?} {}", death.unit_type, death.entity),
+        },
+    );
+}
+
+fn watch_recruits(
+    trigger: On<RecruitEvent>,
+    mut watched: ResMut<WatchedEvents>,
+    scenes: Query<&GameSceneId>,
+    players: Query<&Player>,
+) {
+    let recruit = trigger.event();
+
+    watched.push(
+        scenes.get(recruit.player).ok(),
+        player_ids(recruit.player, &players),
+        BrpGameEventKind::Recruit {
+            unit: format!("{:?}", recruit.unit_type),
+        },
+    );
+}
+
+fn watch_buildings(
+    mut change_start: MessageReader<BuildingChangeStart>,
+    mut change_end: MessageReader<BuildingChangeEnd>,
+    mut watched: ResMut<WatchedEvents>,
+    scenes: Query<&GameSceneId>,
+    players: Query<&Player>,
+) {
+    for start in change_start.read() {
+        watched.push(
+            scenes.get(start.building_entity).ok(),
+            player_ids(start.player_entity, &players),
+            BrpGameEventKind::BuildingChangeStart {
+                building: format!(
+                    "{:?} {}",
+                    start.building.building_type, start.building_entity
+                ),
+            },
+        );
+    }
+
+    for end in change_end.read() {
+        watched.push(
+            scenes.get(end.building_entity).ok(),
+            player_ids(end.player_entity, &players),
+            BrpGameEventKind::BuildingChangeEnd {
+                building: format!("{:?} {}", end.building.building_type, end.building_entity),
+            },
+        );
+    }
+}
+
+fn watch_flags(
+    mut pick_flag: MessageReader<PickFlagEvent>,
+    mut drop_flag: MessageReader<DropFlagEvent>,
+    mut watched: ResMut<WatchedEvents>,
+    scenes: Query<&GameSceneId>,
+    players: Query<&Player>,
+) {
+    for pick in pick_flag.read() {
+        watched.push(
+            scenes.get(pick.player).ok(),
+            player_ids(pick.player, &players),
+            BrpGameEventKind::FlagPicked {
+                flag: format!("flag {}", pick.flag),
+            },
+        );
+    }
+
+    for drop in drop_flag.read() {
+        watched.push(
+            scenes.get(drop.player).ok(),
+            player_ids(drop.player, &players),
+            BrpGameEventKind::FlagDropped {
+                flag: format!("flag {}", drop.flag),
+            },
+        );
+    }
+}
+
+fn watch_defeats(
+    buildings: Query<(&Building, &BuildStatus, &Owner, Option<&GameSceneId>), Changed<BuildStatus>>,
+    mut watched: ResMut<WatchedEvents>,
+    players: Query<&Player>,
+) {
+    for (building, status, owner, scene) in buildings.iter() {
+        let BuildingType::MainBuilding { level: _ } = building.building_type else {
+            continue;
+        };
+        let BuildStatus::Destroyed = status else {
+            continue;
+        };
+
+        watched.push(
+            scene,
+            owner_ids(Some(owner), &players),
+            BrpGameEventKind::PlayerDefeated,
+        );
+    }
+}
+
+/// Runs once per frame for every open watch stream and sends the matching events, if any.
+pub(super) fn watch_events(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult<Option<Value>> {
+    if world.resource::<WatchedEvents>().0.is_empty() {
+        return Ok(None);
+    }
+
+    let filter: BrpWatchEvents = match params {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| invalid_params(format!("invalid watch parameters: {e}")))?,
+        None => BrpWatchEvents::default(),
+    };
+
+    let player = match &filter.player {
+        Some(selector) => {
+            let entity = resolve_player(world, selector)?;
+            let player = world
+                .get::<Player>(entity)
+                .ok_or_else(|| player_not_found(selector))?;
+            Some(player.id)
+        }
+        None => None,
+    };
+
+    let events: Vec<&BrpGameEvent> = world
+        .resource::<WatchedEvents>()
+        .0
+        .iter()
+        .filter(|event| player.is_none_or(|id| event.players.contains(&id)))
+        .filter(|event| filter.scene.is_none_or(|scene| event.scene == Some(scene)))
+        .collect();
+
+    if events.is_empty() {
+        return Ok(None);
+    }
+
+    serde_json::to_value(events)
+        .map(Some)
+        .map_err(BrpError::internal)
+}
@@ -10,13 +10,17 @@ use crate::{
     enum_map::*,
     map::Layers,
     networking::UnitType,
+    player_attacks::{ArmyOrder, OrderBuildings, give_order, order_behaviour},
     server::{
         buildings::{
-            recruiting::{Flag, FlagAssignment, FlagHolder},
+            recruiting::{Flag, FlagAssignment, FlagHolder, FlagUnits},
             siege_camp::SiegeCamp,
         },
         physics::attachment::AttachedTo,
-        players::interaction::{Interactable, InteractionTriggeredEvent, InteractionType},
+        players::{
+            interaction::{Interactable, InteractionTriggeredEvent, InteractionType},
+            team::Factions,
+        },
     },
 };
 
@@ -77,15 +81,31 @@ pub struct CommanderPickFlag(pub usize);
 #[derive(Event, Serialize, Deserialize)]
 pub struct CommanderAssignmentRequest(pub usize);
 
+/// [`ArmyOrder`] for the whole army of the commander picked in the menu.
+#[derive(Event, Serialize, Deserialize)]
+pub struct CommanderOrder(pub ArmyOrder);
+
 #[derive(Event, Serialize, Deserialize)]
 pub struct CommanderAssignmentReject(pub usize);
 
+/// Assigns the flag held by `player` to a slot in the army of `commander`.
 #[derive(Event, Serialize, Deserialize)]
 pub struct Assignment {
     player: Entity,
+    commander: Entity,
     slot: ArmyPosition,
 }
 
+impl Assignment {
+    pub fn new(player: Entity, commander: Entity, slot: ArmyPosition) -> Self {
+        Self {
+            player,
+            commander,
+            slot,
+        }
+    }
+}
+
 pub const BASE_FORMATION_WIDTH: f32 = 50.;
 pub const BASE_FORMATION_OFFSET: f32 = 5.;
 
@@ -121,6 +141,7 @@ impl Plugin for CommanderPlugin {
             .add_observer(commander_assignment_validation)
             .add_observer(handle_slot_selection)
             .add_observer(handle_camp_interaction)
+            .add_observer(handle_commander_order)
             .add_observer(assign_flag_to_formation)
             .add_observer(remove_flag_from_formation)
             .add_observer(swap_flag_from_formation)
@@ -188,6 +209,7 @@ fn handle_pick_flag(
                 .for_each(|(formation, _)| {
                     commands.trigger(Assignment {
                         player: *player,
+                        commander: *commander,
                         slot: formation,
                     });
                 });
@@ -220,8 +242,48 @@ fn handle_camp_interaction(
     Ok(())
 }
 
+fn handle_commander_order(
+    trigger: On<FromClient<CommanderOrder>>,
+    active: Res<ActiveCommander>,
+    client_player_map: ResMut<ClientPlayerMap>,
+    commander_flag_assignment: Query<&FlagAssignment>,
+    scenes: Query<&GameSceneId>,
+    units: Query<&FlagUnits>,
+    army: Query<&ArmyFlagAssignments>,
+    buildings: OrderBuildings,
+    transforms: Query<&Transform>,
+    factions: Factions,
+    mut commands: Commands,
+) -> Result {
+    let player = client_player_map.get_player(&trigger.client_id)?;
+    let commander = active.get_entity(player)?;
+    let commander_flag = commander_flag_assignment.get(*commander)?;
+
+    let Some(new_behaviour) = order_behaviour(
+        trigger.0,
+        *player,
+        transforms.get(*player)?,
+        scenes.get(*player)?,
+        &buildings,
+        &factions,
+    ) else {
+        return Ok(());
+    };
+
+    give_order(
+        **commander_flag,
+        *commander,
+        new_behaviour,
+        &units,
+        &army,
+        &transforms,
+        &mut commands,
+    )
+}
+
 fn commander_assignment_validation(
     trigger: On<FromClient<ArmyPosition>>,
+    active: Res<ActiveCommander>,
     client_player_map: ResMut<ClientPlayerMap>,
     flag_holder: Query<&FlagHolder>,
     flag: Query<&Flag>,
@@ -243,6 +305,7 @@ fn commander_assignment_validation(
 
     commands.trigger(Assignment {
         player: *player,
+        commander: *active.get_entity(player)?,
         slot: trigger.message,
     });
     Ok(())
@@ -250,13 +313,12 @@ fn commander_assignment_validation(
 
 fn handle_slot_selection(
     trigger: On<Assignment>,
-    active: Res<ActiveCommander>,
     formations: Query<&ArmyFlagAssignments>,
     mut commands: Commands,
     flag_holder: Query<&FlagHolder>,
 ) -> Result {
     let player = &trigger.player;
-    let commander = active.get_entity(player)?;
+    let commander = &trigger.commander;
     let formation = formations.get(*commander)?;
 
     let selected_slot = trigger.slot;
@@ -9,9 +9,11 @@ use crate::{
     map::buildings::{BuildStatus, Building, BuildingType, HealthIndicator},
     networking::{UnitType, WorldDirection},
     server::{
-        ai::{BanditBehaviour, BehaveSources, Target, TargetedBy, UnitBehaviour},
+        ai::{
+            BanditBehaviour, BehaveSources, Target, TargetedBy, UnitBehaviour, morale::MoraleState,
+        },
         buildings::recruiting::{FlagAssignment, FlagHolder, FlagUnits},
-        entities::Unit,
+        entities::{Armor, Unit},
         physics::{attachment::AttachedTo, movement::Velocity},
         players::{
             flag::FlagDestroyed,
@@ -22,6 +24,12 @@ use crate::{
 
 use super::commander::ArmyFlagAssignments;
 
+/// Multiplier on damage taken from the side a unit is facing away from.
+const FLANK_MULTIPLIER: f32 = 1.5;
+
+/// Share of a frontal hit a shield lets through when it doesn't block it outright.
+const SHIELD_REDUCTION: f32 = 0.6;
+
 #[derive(Component, Clone, Copy)]
 pub struct Health {
     pub hitpoints: f32,
@@ -33,12 +41,64 @@ impl Default for Health {
     }
 }
 
+impl Health {
+    /// Heals by `amount`, never beyond `max`.
+    pub fn heal(&mut self, amount: f32, max: &MaxHealth) {
+        self.hitpoints = (self.hitpoints + amount).min(**max).max(self.hitpoints);
+    }
+}
+
+/// Hitpoints at full health.
+///
+/// Inserting [`Health`] always means full health, so its hitpoints become the new maximum.
+#[derive(Component, Clone, Copy, Debug, Deref)]
+pub struct MaxHealth(pub f32);
+
+/// Share of [`MaxHealth`] left, replicated to clients for health bars.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Deref, Serialize, Deserialize)]
+pub struct HealthFraction(pub f32);
+
+/// Kind of damage dealt, physical damage is mitigated by armor and magical damage by magic resist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Ice,
+}
+
+impl DamageType {
+    pub fn is_magical(&self) -> bool {
+        match self {
+            DamageType::Physical => false,
+            DamageType::Fire | DamageType::Ice => true,
+        }
+    }
+}
+
 #[derive(Message, Debug, Clone)]
 pub struct TakeDamage {
     pub target_entity: Entity,
     pub damage: f32,
+    pub damage_type: DamageType,
     pub direction: WorldDirection,
     pub by: Hitby,
+    /// The unit that dealt the damage, if any.
+    pub attacker: Option<Entity>,
+}
+
+/// A [`TakeDamage`] that landed, with the hitpoints the target actually lost.
+///
+/// Written once blocks, flanks, armor and morale are resolved. Blocked hits never show up here,
+/// so everything reacting to a hit reads this instead of [`TakeDamage`].
+#[derive(Message, Debug, Clone, Deref)]
+pub struct DamageApplied(pub TakeDamage);
+
+/// Triggered on the server when a unit runs out of hitpoints.
+#[derive(Event, Clone, Copy)]
+pub struct UnitDied {
+    pub entity: Entity,
+    pub unit_type: UnitType,
+    pub owner: Owner,
 }
 
 #[derive(Component)]
@@ -57,10 +117,13 @@ impl DelayedDamage {
             UnitType::Commander => 2,
         };
 
-        let duration = frame_delay as f32 * 0.1;
+        Self::after(frame_delay as f32 * 0.1, damage)
+    }
 
+    /// Deals `damage` once `seconds` have passed.
+    pub fn after(seconds: f32, damage: TakeDamage) -> Self {
         Self {
-            timer: Timer::from_seconds(duration, TimerMode::Once),
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
             damage,
         }
     }
@@ -70,7 +133,10 @@ pub struct HealthPlugin;
 
 impl Plugin for HealthPlugin {
     fn build(&self, app: &mut App) {
-        app.add_message::<TakeDamage>();
+        app.add_message::<TakeDamage>()
+            .add_message::<DamageApplied>()
+            .add_observer(set_max_health)
+            .add_observer(remove_health_fraction);
 
         app.add_systems(
             FixedUpdate,
@@ -79,7 +145,7 @@ impl Plugin for HealthPlugin {
                     delayed_damage,
                     apply_damage,
                     (on_building_destroy, on_unit_death),
-                    update_build_status,
+                    (update_build_status, update_health_fraction),
                 )
                     .chain(),
                 delayed_despawn,
@@ -105,12 +171,58 @@ fn delayed_damage(
 
 fn apply_damage(
     mut attack_events: MessageReader<TakeDamage>,
-    mut query: Query<(Entity, &mut Health)>,
+    mut query: Query<(
+        Entity,
+        &mut Health,
+        &Transform,
+        Option<&Unit>,
+        Option<&MoraleState>,
+        Option<&Armor>,
+    )>,
+    mut applied: MessageWriter<DamageApplied>,
     mut animation: MessageWriter<ToClients<AnimationChangeEvent>>,
 ) {
     for event in attack_events.read() {
-        if let Ok((entity, mut health)) = query.get_mut(event.target_entity) {
-            health.hitpoints -= event.damage;
+        if let Ok((entity, mut health, transform, unit, morale, armor)) =
+            query.get_mut(event.target_entity)
+        {
+            let directional = match unit {
+                Some(unit) if !matches!(event.by, Hitby::Status) => {
+                    let facing: WorldDirection = transform.scale.x.into();
+                    if facing == event.direction {
+                        // The hit travels the way the unit faces, so it lands in its back.
+                        FLANK_MULTIPLIER
+                    } else if let UnitType::Shieldwarrior = unit.unit_type {
+                        if fastrand::f32() < event.by.block_chance() {
+                            animation.write(ToClients {
+                                mode: SendMode::Broadcast,
+                                message: AnimationChangeEvent {
+                                    entity,
+                                    change: AnimationChange::Block,
+                                },
+                            });
+                            continue;
+                        }
+                        SHIELD_REDUCTION
+                    } else {
+                        1.
+                    }
+                }
+                _ => 1.,
+            };
+
+            let multiplier = morale.map(MoraleState::damage_multiplier).unwrap_or(1.);
+            let mitigation = armor
+                .map(|armor| armor.mitigation(event.damage_type, event.by))
+                .unwrap_or(1.);
+            // A hit can't take more hitpoints than the target has left.
+            let damage = (event.damage * multiplier * mitigation * directional)
+                .min(health.hitpoints.max(0.));
+            health.hitpoints -= damage;
+            applied.write(DamageApplied(TakeDamage {
+                damage,
+                ..event.clone()
+            }));
 
             animation.write(ToClients {
                 mode: SendMode::Broadcast,
@@ -123,9 +235,42 @@ fn apply_damage(
     }
 }
 
-fn update_build_status(mut query: Query<(&Health, &mut BuildStatus, &Building), Changed<Health>>) {
-    for (health, mut status, building) in query.iter_mut() {
-        let percentage = health.hitpoints / building.health().hitpoints * 100.0;
+fn set_max_health(trigger: On<Insert, Health>, query: Query<&Health>, mut commands: Commands) {
+    let Ok(health) = query.get(trigger.entity) else {
+        return;
+    };
+    commands
+        .entity(trigger.entity)
+        .insert((MaxHealth(health.hitpoints), HealthFraction(1.)));
+}
+
+/// Dead units and destroyed buildings don't show a health bar.
+fn remove_health_fraction(trigger: On<Remove, Health>, mut commands: Commands) {
+    commands
+        .entity(trigger.entity)
+        .try_remove::<HealthFraction>();
+}
+
+fn update_health_fraction(
+    mut query: Query<
+        (&Health, &MaxHealth, &mut HealthFraction),
+        Or<(Changed<Health>, Changed<MaxHealth>)>,
+    >,
+) {
+    for (health, max_health, mut fraction) in query.iter_mut() {
+        // Rounded to whole percents, so regeneration doesn't replicate every tick.
+        let percent = (health.hitpoints / **max_health * 100.)
+            .round()
+            .clamp(0., 100.);
+        fraction.set_if_neq(HealthFraction(percent / 100.));
+    }
+}
+
+fn update_build_status(
+    mut query: Query<(&Health, &MaxHealth, &mut BuildStatus), (Changed<Health>, With<Building>)>,
+) {
+    for (health, max_health, mut status) in query.iter_mut() {
+        let percentage = health.hitpoints / **max_health * 100.0;
         let percentage_i32 = percentage.clamp(0.0, 100.0) as i32;
 
         let severity = match percentage_i32 {
@@ -146,11 +291,12 @@ fn update_build_status(mut query: Query<(&Health, &mut BuildStatus, &Building),
 }
 
 fn on_unit_death(
-    mut damage_events: MessageReader<TakeDamage>,
+    mut damage_events: MessageReader<DamageApplied>,
     mut unit_animation: MessageWriter<ToClients<AnimationChangeEvent>>,
     units: Query<
         (
             Entity,
+            &Unit,
             &Health,
             &Owner,
             Option<&TargetedBy>,
@@ -165,7 +311,7 @@ fn on_unit_death(
     mut commands: Commands,
 ) -> Result {
     for damage_event in damage_events.read() {
-        let Ok((entity, health, owner, maybe_targeted_by, maybe_flag_assignment, maybe_army)) =
+        let Ok((entity, unit, health, owner, maybe_targeted_by, maybe_flag_assignment, maybe_army)) =
             units.get(damage_event.target_entity)
         else {
             continue;
@@ -189,6 +335,12 @@ fn on_unit_death(
             },
         });
 
+        commands.trigger(UnitDied {
+            entity,
+            unit_type: unit.unit_type,
+            owner: *owner,
+        });
+
         if let Some(targeted_by) = maybe_targeted_by {
             commands
                 .entity(entity)
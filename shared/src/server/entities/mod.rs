@@ -4,18 +4,28 @@ use bevy::sprite::Anchor;
 use bevy_replicon::prelude::Replicated;
 use commander::CommanderPlugin;
 use enum_mappable::Mappable;
-use health::{Health, HealthPlugin};
+use health::{DamageType, Health, HealthPlugin};
+use regeneration::RegenerationPlugin;
 use serde::{Deserialize, Serialize};
+use status::{StatusEffects, StatusPlugin};
 
-use crate::{BoxCollider, PlayerColor, enum_map::EnumIter, networking::UnitType, unit_collider};
+use crate::{
+    BoxCollider, Hitby, PlayerColor, enum_map::EnumIter, networking::UnitType, unit_collider,
+};
 
-use super::physics::{
-    PushBack,
-    movement::{RandomVelocityMul, Speed, Velocity},
+use super::{
+    ai::morale::Morale,
+    physics::{
+        PushBack,
+        movement::{RandomVelocityMul, Speed, Velocity},
+        projectile::ProjectileType,
+    },
 };
 
 pub mod commander;
 pub mod health;
+pub mod regeneration;
+pub mod status;
 
 #[derive(Component, PartialEq, Eq, Debug, Clone, Copy, Mappable, Default)]
 pub enum UnitAnimation {
@@ -24,6 +34,7 @@ pub enum UnitAnimation {
     Walk,
     Attack,
     Hit,
+    Block,
     Death,
 }
 
@@ -42,7 +53,9 @@ pub enum UnitAnimation {
     MeleeRange,
     Speed,
     Damage,
-    Sight
+    Sight,
+    Morale,
+    StatusEffects
 )]
 pub struct Unit {
     pub unit_type: UnitType,
@@ -65,6 +78,39 @@ pub struct MeleeRange(pub f32);
 #[derive(Component, Debug, Copy, Clone, Deref, DerefMut)]
 pub struct ProjectileRange(pub f32);
 
+/// What a unit shoots, given by its projectile weapon.
+#[derive(Component, Debug, Copy, Clone, Deref)]
+pub struct Ammunition(pub ProjectileType);
+
+impl Default for Ammunition {
+    fn default() -> Self {
+        Self(ProjectileType::Arrow)
+    }
+}
+
+/// How precisely projectiles are aimed, from 0 to 1.
+#[derive(Component, Debug, Copy, Clone, Deref, DerefMut)]
+pub struct Accuracy(pub f32);
+
+/// Armor ratings against physical and magical damage, 100 armor halves the damage taken.
+#[derive(Component, Debug, Copy, Clone, Default)]
+pub struct Armor {
+    pub physical: f32,
+    pub magical: f32,
+}
+
+impl Armor {
+    /// Fraction of the damage that gets through the armor.
+    pub fn mitigation(&self, damage_type: DamageType, by: Hitby) -> f32 {
+        let rating = if damage_type.is_magical() {
+            self.magical
+        } else {
+            self.physical * by.armor_effectiveness()
+        };
+        100. / (100. + rating.max(0.))
+    }
+}
+
 impl Default for MeleeRange {
     fn default() -> Self {
         Self(10.)
@@ -86,13 +132,16 @@ impl Plugin for EntityPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(HealthPlugin);
         app.add_plugins(CommanderPlugin);
+        app.add_plugins(StatusPlugin);
+        app.add_plugins(RegenerationPlugin);
 
         app.add_systems(FixedUpdate, unit_swing_timer);
     }
 }
 
-fn unit_swing_timer(mut query: Query<&mut Unit, With<Health>>, time: Res<Time>) {
-    for mut unit in query.iter_mut() {
-        unit.swing_timer.tick(time.delta());
+fn unit_swing_timer(mut query: Query<(&mut Unit, &StatusEffects), With<Health>>, time: Res<Time>) {
+    for (mut unit, effects) in query.iter_mut() {
+        unit.swing_timer
+            .tick(time.delta().mul_f32(effects.speed_multiplier()));
     }
 }
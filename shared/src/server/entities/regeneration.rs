@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use bevy_replicon::prelude::ClientState;
+
+use crate::{
+    GameSceneId, Owner,
+    map::buildings::{Building, BuildingType, RespawnZone},
+};
+
+use super::health::{DamageApplied, Health, MaxHealth};
+
+/// Seconds without taking damage before regeneration starts.
+const OUT_OF_COMBAT_DELAY: f32 = 8.;
+
+/// Hitpoints regenerated per second.
+const REGENERATION_RATE: f32 = 5.;
+
+/// Distance to the player's respawn zone or main building within which units regenerate.
+const REGENERATION_RADIUS: f32 = 300.;
+
+pub struct RegenerationPlugin;
+
+impl Plugin for RegenerationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (record_last_hit, regenerate)
+                .chain()
+                .run_if(in_state(ClientState::Disconnected)),
+        );
+    }
+}
+
+/// Game time of the last hit taken.
+#[derive(Component, Clone, Copy)]
+struct LastHit(f32);
+
+fn record_last_hit(
+    mut damage: MessageReader<DamageApplied>,
+    health: Query<(), With<Health>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for event in damage.read() {
+        if health.contains(event.target_entity) {
+            commands
+                .entity(event.target_entity)
+                .try_insert(LastHit(time.elapsed_secs()));
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn regenerate(
+    mut wounded: Query<
+        (
+            &mut Health,
+            &MaxHealth,
+            &Transform,
+            &Owner,
+            &GameSceneId,
+            Option<&LastHit>,
+        ),
+        Without<Building>,
+    >,
+    bases: Query<
+        (
+            &Transform,
+            &Owner,
+            &GameSceneId,
+            Option<&Building>,
+            Has<RespawnZone>,
+        ),
+        Or<(With<RespawnZone>, With<Building>)>,
+    >,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_secs();
+
+    for (mut health, max_health, transform, owner, game_scene_id, last_hit) in wounded.iter_mut() {
+        if health.hitpoints >= **max_health || health.hitpoints <= 0. {
+            continue;
+        }
+        if last_hit.is_some_and(|LastHit(at)| now - at < OUT_OF_COMBAT_DELAY) {
+            continue;
+        }
+        let Owner::Player(_) = owner else {
+            continue;
+        };
+
+        let at_base = bases.iter().any(
+            |(base_transform, base_owner, base_scene_id, building, is_respawn_zone)| {
+                let is_base = is_respawn_zone
+                    || building.is_some_and(|building| {
+                        matches!(building.building_type, BuildingType::MainBuilding { .. })
+                    });
+                is_base
+                    && base_owner == owner
+                    && base_scene_id == game_scene_id
+                    && (base_transform.translation.x - transform.translation.x).abs()
+                        <= REGENERATION_RADIUS
+            },
+        );
+        if at_base {
+            health.heal(REGENERATION_RATE * time.delta_secs(), max_health);
+        }
+    }
+}
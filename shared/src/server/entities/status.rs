@@ -0,0 +1,283 @@
+use bevy::prelude::*;
+
+use bevy_replicon::prelude::ClientState;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Hitby,
+    networking::{UnitType, WorldDirection},
+};
+
+use super::{
+    Unit,
+    health::{DamageApplied, DamageType, Health, TakeDamage, UnitDied},
+};
+
+/// Seconds between the damage ticks of burning and bleeding.
+const DAMAGE_TICK: f32 = 1.;
+
+/// Share of a fire hit dealt again every tick while burning.
+const BURNING_SHARE: f32 = 0.2;
+const BURNING_DURATION: f32 = 4.;
+
+/// Fraction by which ice hits slow movement and attacks.
+const CHILL_SLOW: f32 = 0.4;
+const CHILL_DURATION: f32 = 3.;
+
+/// Explosions daze the units they hit, slowing them a little.
+const DAZE_SLOW: f32 = 0.3;
+const DAZE_DURATION: f32 = 2.;
+
+/// Share of a pike hit dealt again every tick while bleeding.
+const BLEEDING_SHARE: f32 = 0.1;
+const BLEEDING_DURATION: f32 = 5.;
+
+/// Share of their damage bleeding units lose.
+const BLEEDING_WEAKNESS: f32 = 0.25;
+
+/// Chance from 0 to 1 that a shield warrior's hit stuns.
+const SHIELD_BASH_CHANCE: f32 = 0.2;
+const STUN_DURATION: f32 = 1.;
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(apply_status)
+            .add_observer(clear_status_on_death)
+            .add_systems(
+                FixedUpdate,
+                (
+                    apply_weapon_effects,
+                    tick_status_effects,
+                    update_active_statuses,
+                )
+                    .chain()
+                    .run_if(in_state(ClientState::Disconnected)),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusKind {
+    Burning,
+    Slowed,
+    Stunned,
+    Bleeding,
+}
+
+#[derive(Clone, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    /// Damage per tick for burning and bleeding, the slowed fraction for slowed.
+    pub magnitude: f32,
+    duration: Timer,
+    tick: Timer,
+    source: Option<Entity>,
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusKind, magnitude: f32, duration: f32) -> Self {
+        Self {
+            kind,
+            magnitude,
+            duration: Timer::from_seconds(duration, TimerMode::Once),
+            tick: Timer::from_seconds(DAMAGE_TICK, TimerMode::Repeating),
+            source: None,
+        }
+    }
+
+    pub fn burning(damage_per_tick: f32, duration: f32) -> Self {
+        Self::new(StatusKind::Burning, damage_per_tick, duration)
+    }
+
+    pub fn bleeding(damage_per_tick: f32, duration: f32) -> Self {
+        Self::new(StatusKind::Bleeding, damage_per_tick, duration)
+    }
+
+    pub fn slowed(fraction: f32, duration: f32) -> Self {
+        Self::new(StatusKind::Slowed, fraction.clamp(0., 1.), duration)
+    }
+
+    pub fn stunned(duration: f32) -> Self {
+        Self::new(StatusKind::Stunned, 1., duration)
+    }
+
+    /// The unit credited with the damage this effect deals.
+    pub fn with_source(mut self, source: Option<Entity>) -> Self {
+        self.source = source;
+        self
+    }
+
+    fn damage_type(&self) -> Option<DamageType> {
+        match self.kind {
+            StatusKind::Burning => Some(DamageType::Fire),
+            StatusKind::Bleeding => Some(DamageType::Physical),
+            StatusKind::Slowed | StatusKind::Stunned => None,
+        }
+    }
+}
+
+/// Timed effects on a unit, at most one per [`StatusKind`].
+#[derive(Component, Default, Deref)]
+#[require(ActiveStatuses)]
+pub struct StatusEffects(Vec<StatusEffect>);
+
+impl StatusEffects {
+    /// Refreshes an effect of the same kind, keeping the stronger magnitude and longer duration.
+    fn add(&mut self, effect: StatusEffect) {
+        let Some(existing) = self.0.iter_mut().find(|other| other.kind == effect.kind) else {
+            self.0.push(effect);
+            return;
+        };
+
+        existing.magnitude = existing.magnitude.max(effect.magnitude);
+        if effect.duration.remaining() > existing.duration.remaining() {
+            existing.duration = effect.duration;
+        }
+        existing.source = effect.source.or(existing.source);
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.0.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// Factor on movement and attack speed.
+    pub fn speed_multiplier(&self) -> f32 {
+        if self.has(StatusKind::Stunned) {
+            return 0.;
+        }
+
+        self.0
+            .iter()
+            .filter(|effect| effect.kind == StatusKind::Slowed)
+            .map(|effect| 1. - effect.magnitude)
+            .product()
+    }
+
+    /// Factor on the damage the unit deals.
+    pub fn damage_multiplier(&self) -> f32 {
+        if self.has(StatusKind::Bleeding) {
+            1. - BLEEDING_WEAKNESS
+        } else {
+            1.
+        }
+    }
+}
+
+/// Kinds of the effects currently on a unit, replicated to clients for tints and icons.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq, Deref, Serialize, Deserialize)]
+pub struct ActiveStatuses(Vec<StatusKind>);
+
+/// Puts a status effect on a unit.
+#[derive(Event, Clone)]
+pub struct ApplyStatus {
+    pub target: Entity,
+    pub effect: StatusEffect,
+}
+
+fn apply_status(
+    trigger: On<ApplyStatus>,
+    mut units: Query<(&mut StatusEffects, &mut Unit), With<Health>>,
+) {
+    let ApplyStatus { target, effect } = trigger.event().clone();
+    let Ok((mut effects, mut unit)) = units.get_mut(target) else {
+        return;
+    };
+
+    if effect.kind == StatusKind::Stunned {
+        unit.swing_timer.reset();
+    }
+    effects.add(effect);
+}
+
+/// Fire hits set units on fire, ice hits and explosions slow them down.
+///
+/// Melee weapons have specials: pikes leave bleeding wounds, shields bash units into a stun.
+fn apply_weapon_effects(
+    mut damage: MessageReader<DamageApplied>,
+    attackers: Query<&Unit>,
+    mut commands: Commands,
+) {
+    for event in damage.read() {
+        let weapon = event
+            .attacker
+            .and_then(|attacker| attackers.get(attacker).ok())
+            .map(|unit| unit.unit_type);
+
+        let effect = match (event.by, event.damage_type, weapon) {
+            (Hitby::Status, ..) => continue,
+            (_, DamageType::Fire, _) => {
+                StatusEffect::burning(event.damage * BURNING_SHARE, BURNING_DURATION)
+            }
+            (_, DamageType::Ice, _) => StatusEffect::slowed(CHILL_SLOW, CHILL_DURATION),
+            (Hitby::Explosion, ..) => StatusEffect::slowed(DAZE_SLOW, DAZE_DURATION),
+            (Hitby::Melee, _, Some(UnitType::Pikeman)) => {
+                StatusEffect::bleeding(event.damage * BLEEDING_SHARE, BLEEDING_DURATION)
+            }
+            (Hitby::Melee, _, Some(UnitType::Shieldwarrior))
+                if fastrand::f32() < SHIELD_BASH_CHANCE =>
+            {
+                StatusEffect::stunned(STUN_DURATION)
+            }
+            _ => continue,
+        };
+
+        commands.trigger(ApplyStatus {
+            target: event.target_entity,
+            effect: effect.with_source(event.attacker),
+        });
+    }
+}
+
+fn tick_status_effects(
+    mut units: Query<(Entity, &mut StatusEffects), With<Health>>,
+    mut damage: MessageWriter<TakeDamage>,
+    time: Res<Time>,
+) {
+    for (entity, mut effects) in units.iter_mut() {
+        if effects.is_empty() {
+            continue;
+        }
+
+        for effect in effects.0.iter_mut() {
+            effect.duration.tick(time.delta());
+
+            let Some(damage_type) = effect.damage_type() else {
+                continue;
+            };
+            effect.tick.tick(time.delta());
+            if !effect.tick.just_finished() {
+                continue;
+            }
+
+            damage.write(TakeDamage {
+                target_entity: entity,
+                damage: effect.magnitude,
+                damage_type,
+                direction: WorldDirection::default(),
+                by: Hitby::Status,
+                attacker: effect.source,
+            });
+        }
+
+        effects.0.retain(|effect| !effect.duration.is_finished());
+    }
+}
+
+fn update_active_statuses(
+    mut units: Query<(&StatusEffects, &mut ActiveStatuses), Changed<StatusEffects>>,
+) {
+    for (effects, mut active) in units.iter_mut() {
+        active.set_if_neq(ActiveStatuses(
+            effects.iter().map(|effect| effect.kind).collect(),
+        ));
+    }
+}
+
+/// Corpses don't keep burning.
+fn clear_status_on_death(trigger: On<UnitDied>, mut query: Query<&mut StatusEffects>) {
+    if let Ok(mut effects) = query.get_mut(trigger.event().entity) {
+        effects.0.clear();
+    }
+}
@@ -6,3 +6,4 @@ pub mod entities;
 pub mod networking;
 pub mod physics;
 pub mod players;
+pub mod stats;
@@ -3,7 +3,7 @@ use bevy::prelude::*;
 use super::{
     ai::AIPlugin, buildings::BuildingsPlugins, console::ConsolePlugin,
     create_server::CreateServerPlugin, entities::EntityPlugin, physics::PhysicsPlugin,
-    players::PlayerPlugin,
+    players::PlayerPlugin, stats::StatsPlugin,
 };
 use crate::networking::NetworkRegistry;
 
@@ -20,6 +20,7 @@ impl Plugin for ServerNetworkPlugin {
             PlayerPlugin,
             EntityPlugin,
             ConsolePlugin,
+            StatsPlugin,
         ));
     }
 }
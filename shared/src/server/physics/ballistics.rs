@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use crate::GRAVITY_G;
+
+/// Refinements of the predicted target position when leading a moving target.
+const LEAD_ITERATIONS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShotArc {
+    /// Flat and fast, the default for targets close by.
+    Low,
+    /// Lobbed, drops steeply onto targets far away or behind walls.
+    High,
+}
+
+/// Tuning of projectile attacks.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Ballistics {
+    /// Launch speed needed to reach the shooter's projectile range is multiplied by this,
+    /// so targets at the edge of the range can still be hit when they are slightly elevated.
+    pub speed_margin: f32,
+    /// Fraction of the projectile range beyond which the high arc is used.
+    pub high_arc_from: f32,
+    /// Spread in radians of a shooter without any accuracy, at point blank.
+    pub base_spread: f32,
+    /// Additional spread in radians at the edge of the projectile range.
+    pub range_spread: f32,
+}
+
+impl Default for Ballistics {
+    fn default() -> Self {
+        Self {
+            speed_margin: 1.1,
+            high_arc_from: 0.7,
+            base_spread: 0.05,
+            range_spread: 0.15,
+        }
+    }
+}
+
+impl Ballistics {
+    /// Launch speed of a projectile fired by a shooter with `range`.
+    ///
+    /// On flat ground a projectile at this speed reaches `range` when fired at 45°.
+    pub fn launch_speed(&self, range: f32) -> f32 {
+        (GRAVITY_G * range.max(1.)).sqrt() * self.speed_margin
+    }
+
+    pub fn arc(&self, distance: f32, range: f32) -> ShotArc {
+        match distance > range * self.high_arc_from {
+            true => ShotArc::High,
+            false => ShotArc::Low,
+        }
+    }
+
+    /// Maximum deviation in radians of a shot over `distance`.
+    ///
+    /// `accuracy` ranges from 0 to 1, a perfectly accurate shooter has no spread.
+    pub fn spread(&self, distance: f32, range: f32, accuracy: f32) -> f32 {
+        let range_fraction = (distance / range.max(1.)).clamp(0., 1.);
+        (self.base_spread + self.range_spread * range_fraction) * (1. - accuracy.clamp(0., 1.))
+    }
+}
+
+/// Launch velocity of a projectile at `speed` that passes through `delta` from its origin.
+///
+/// Returns `None` if `delta` can't be reached at this speed.
+pub fn launch_velocity(delta: Vec2, speed: f32, arc: ShotArc) -> Option<Vec2> {
+    let distance = delta.x.abs();
+    if distance <= f32::EPSILON {
+        return None;
+    }
+
+    let speed_squared = speed * speed;
+    let discriminant = speed_squared * speed_squared
+        - GRAVITY_G * (GRAVITY_G * distance * distance + 2. * delta.y * speed_squared);
+    if discriminant < 0. {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    let tan_theta = match arc {
+        ShotArc::Low => (speed_squared - root) / (GRAVITY_G * distance),
+        ShotArc::High => (speed_squared + root) / (GRAVITY_G * distance),
+    };
+    let theta = tan_theta.atan();
+
+    Some(Vec2::new(delta.x.signum() * theta.cos(), theta.sin()) * speed)
+}
+
+/// Launch velocity that hits a target moving along x at `target_velocity`.
+///
+/// The target position is predicted from the flight time of the previous solution,
+/// falling back to the unpredicted shot if the predicted position is out of reach.
+pub fn lead_target(
+    origin: Vec2,
+    target: Vec2,
+    target_velocity: f32,
+    speed: f32,
+    arc: ShotArc,
+) -> Option<Vec2> {
+    let mut velocity = launch_velocity(target - origin, speed, arc)?;
+    let mut aim = target;
+
+    for _ in 0..LEAD_ITERATIONS {
+        let flight_time = (aim.x - origin.x).abs() / velocity.x.abs().max(f32::EPSILON);
+        let predicted = target + Vec2::X * target_velocity * flight_time;
+
+        match launch_velocity(predicted - origin, speed, arc) {
+            Some(led) => {
+                velocity = led;
+                aim = predicted;
+            }
+            None => break,
+        }
+    }
+
+    Some(velocity)
+}
+
+/// Rotates `velocity` by a random angle of at most `spread` radians.
+pub fn scatter(velocity: Vec2, spread: f32) -> Vec2 {
+    let angle = (fastrand::f32() * 2. - 1.) * spread;
+    Vec2::from_angle(angle).rotate(velocity)
+}
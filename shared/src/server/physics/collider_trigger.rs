@@ -1,10 +1,12 @@
 use bevy::{math::bounding::IntersectsVolume, prelude::*};
 
 use crate::{
-    BoxCollider, Player,
+    BoxCollider, GameSceneId, Player,
     server::players::interaction::{ActiveInteraction, InteractionTriggeredEvent, InteractionType},
 };
 
+use super::spatial::SpatialIndex;
+
 #[derive(Component)]
 pub enum ColliderTrigger {
     Travel,
@@ -21,15 +23,19 @@ impl Plugin for ColliderTriggerPlugin {
 fn check_collider_trigger(
     players: Query<Entity, (With<Player>, Without<ActiveInteraction>)>,
     triggers: Query<(Entity, &ColliderTrigger, &Transform, &BoxCollider)>,
-    player_query: Query<(&Transform, &BoxCollider)>,
+    player_query: Query<(&Transform, &BoxCollider, &GameSceneId)>,
+    index: Res<SpatialIndex>,
     mut interaction: MessageWriter<InteractionTriggeredEvent>,
     mut commands: Commands,
 ) -> Result {
     for player in players.iter() {
-        let (player_transform, player_collider) = player_query.get(player)?;
+        let (player_transform, player_collider, game_scene_id) = player_query.get(player)?;
         let player_bounds = player_collider.at(player_transform);
 
-        for (entity, trigger, transform, collider) in triggers.iter() {
+        for candidate in index.overlapping(*game_scene_id, &player_bounds) {
+            let Ok((entity, trigger, transform, collider)) = triggers.get(candidate) else {
+                continue;
+            };
             if !(player_bounds.intersects(&collider.at(transform))) {
                 continue;
             }
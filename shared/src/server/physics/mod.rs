@@ -3,18 +3,23 @@ use std::time::Duration;
 use bevy::prelude::*;
 
 use attachment::AttachmentPlugin;
+use ballistics::Ballistics;
 use movement::{MovementPlugin, Velocity};
 use projectile::ProjectilePlugin;
+use spatial::SpatialIndexPlugin;
 
+use crate::Hitby;
 use crate::server::physics::{army_slot::ArmySlotPlugin, collider_trigger::ColliderTriggerPlugin};
 
-use super::entities::health::TakeDamage;
+use super::entities::health::DamageApplied;
 
 pub mod army_slot;
 pub mod attachment;
+pub mod ballistics;
 pub mod collider_trigger;
 pub mod movement;
 pub mod projectile;
+pub mod spatial;
 
 pub struct PhysicsPlugin;
 
@@ -26,7 +31,9 @@ impl Plugin for PhysicsPlugin {
             AttachmentPlugin,
             ArmySlotPlugin,
             ColliderTriggerPlugin,
+            SpatialIndexPlugin,
         ))
+        .init_resource::<Ballistics>()
         .add_systems(FixedUpdate, (apply_force_on_hit, push_back_timer));
     }
 }
@@ -44,10 +51,13 @@ impl Default for PushBack {
     }
 }
 fn apply_force_on_hit(
-    mut hit: MessageReader<TakeDamage>,
+    mut hit: MessageReader<DamageApplied>,
     mut query: Query<(&mut Velocity, &mut PushBack)>,
 ) {
     for event in hit.read() {
+        if let Hitby::Status = event.by {
+            continue;
+        }
         if let Ok((mut velocity, mut push_back)) = query.get_mut(event.target_entity)
             && push_back.timer.is_finished()
         {
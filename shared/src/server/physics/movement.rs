@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
 
 use bevy::math::bounding::IntersectsVolume;
 use bevy_replicon::prelude::ClientState;
@@ -8,10 +8,23 @@ use crate::{
     BoxCollider, GRAVITY_G, GameSceneId, Owner, Player,
     map::buildings::{BuildStatus, Building, BuildingType},
     networking::WorldDirection,
-    server::{entities::health::Health, physics::army_slot::ArmySlot, players::items::Item},
+    server::{
+        entities::{Unit, health::Health, status::StatusEffects},
+        physics::army_slot::ArmySlot,
+        players::{items::Item, team::Factions},
+    },
 };
 
-use super::projectile::ProjectileType;
+use super::{projectile::ProjectileType, spatial::SpatialIndex};
+
+/// Allied units closer than this are pushed apart.
+const ALLY_SPACING: f32 = 10.;
+
+/// Enemy units stop walking into each other at this distance.
+const ENEMY_SPACING: f32 = 8.;
+
+/// Speed at which two allies on the same spot are pushed apart.
+const SEPARATION_SPEED: f32 = 60.;
 
 #[derive(Component, Debug, Default, Copy, Clone)]
 pub struct Velocity(pub Vec2);
@@ -72,7 +85,11 @@ impl Plugin for MovementPlugin {
         );
         app.add_systems(
             FixedPostUpdate,
-            (apply_gravity, (apply_velocity, apply_direction))
+            (
+                apply_gravity,
+                separate_units,
+                (apply_velocity, apply_direction),
+            )
                 .chain()
                 .run_if(in_state(ClientState::Disconnected)),
         );
@@ -93,12 +110,86 @@ fn apply_gravity(mut query: Query<(&mut Velocity, &Transform, &BoxCollider)>, ti
     }
 }
 
+/// Keeps units of a faction apart and stops units from walking through their enemies.
+///
+/// Allies are pushed apart the more they overlap, enemies block each other's walking.
+/// A push never moves a unit into an enemy, so melee lines hold.
+#[allow(clippy::type_complexity)]
+fn separate_units(
+    mut units: Query<
+        (Entity, &mut Velocity, &mut Transform, &Owner, &GameSceneId),
+        (With<Unit>, With<Health>),
+    >,
+    factions: Factions,
+    index: Res<SpatialIndex>,
+    time: Res<Time>,
+) {
+    let positions: HashMap<Entity, (f32, Owner)> = units
+        .iter()
+        .map(|(entity, _, transform, owner, _)| (entity, (transform.translation.x, *owner)))
+        .collect();
+
+    for (entity, mut velocity, mut transform, owner, game_scene_id) in units.iter_mut() {
+        let x = transform.translation.x;
+        let mut push = 0.;
+        let mut blocked_left = false;
+        let mut blocked_right = false;
+
+        for other in index.near(*game_scene_id, x, ALLY_SPACING.max(ENEMY_SPACING)) {
+            if other == entity {
+                continue;
+            }
+            let Some((other_x, other_owner)) = positions.get(&other) else {
+                continue;
+            };
+
+            let offset = x - other_x;
+            let distance = offset.abs();
+
+            if factions.is_allied(owner, other_owner) {
+                if distance >= ALLY_SPACING {
+                    continue;
+                }
+                // Units on the exact same spot are split up by entity order.
+                let side = if offset != 0. {
+                    offset.signum()
+                } else if entity > other {
+                    1.
+                } else {
+                    -1.
+                };
+                push += side * (1. - distance / ALLY_SPACING);
+            } else if distance < ENEMY_SPACING {
+                if offset > 0. {
+                    blocked_left = true;
+                } else if offset < 0. {
+                    blocked_right = true;
+                }
+            }
+        }
+
+        let blocked = |dx: f32| (dx < 0. && blocked_left) || (dx > 0. && blocked_right);
+
+        if blocked(velocity.0.x) {
+            velocity.0.x = 0.;
+        }
+
+        let push = push.clamp(-1., 1.) * SEPARATION_SPEED * time.delta_secs();
+        if push != 0. && !blocked(push) {
+            transform.translation.x += push;
+        }
+    }
+}
+
+/// Moves entities by their velocity, slowed units walk slower and stunned ones not at all.
 fn apply_velocity(
-    mut query: Query<(&Velocity, &mut Transform), Changed<Velocity>>,
+    mut query: Query<(&Velocity, &mut Transform, Option<&StatusEffects>), Changed<Velocity>>,
     time: Res<Time>,
 ) {
-    for (velocity, mut transform) in query.iter_mut() {
-        transform.translation += velocity.0.extend(0.) * time.delta_secs();
+    for (velocity, mut transform, effects) in query.iter_mut() {
+        let multiplier = effects.map_or(1., StatusEffects::speed_multiplier);
+        let velocity = velocity.0 * Vec2::new(multiplier, 1.);
+        transform.translation += velocity.extend(0.) * time.delta_secs();
     }
 }
 
@@ -190,6 +281,10 @@ fn set_projectile_rotation(
     mut projectiles: Query<(&mut Transform, &Velocity), With<ProjectileType>>,
 ) {
     for (mut transform, velocity) in projectiles.iter_mut() {
+        // Stuck projectiles keep the angle they hit at.
+        if velocity.0 == Vec2::ZERO {
+            continue;
+        }
         let angle = velocity.0.to_angle();
         transform.rotation = Quat::from_rotation_z(angle);
     }
@@ -198,6 +293,7 @@ fn set_projectile_rotation(
 fn wall_collision(
     mut query: Query<(&mut Velocity, &Transform, &BoxCollider, &Owner)>,
     buildings: Query<(&Transform, &BoxCollider, &Owner, &Building, &BuildStatus), With<Health>>,
+    factions: Factions,
     time: Res<Time>,
 ) {
     for (mut velocity, transform, collider, owner) in query.iter_mut() {
@@ -207,7 +303,7 @@ fn wall_collision(
         for (building_transform, building_collider, building_owner, building, building_status) in
             buildings.iter()
         {
-            if building_owner.is_same_faction(owner) {
+            if factions.is_allied(building_owner, owner) {
                 continue;
             }
 
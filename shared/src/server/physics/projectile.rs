@@ -7,73 +7,319 @@ use serde::{Deserialize, Serialize};
 use crate::Hitby;
 use crate::server::entities::Damage;
 use crate::{
-    BoxCollider, DelayedDespawn, Owner, projectile_collider,
-    server::entities::health::{Health, TakeDamage},
+    BoxCollider, DelayedDespawn, GameSceneId, Owner,
+    map::buildings::Building,
+    projectile_collider,
+    server::{
+        entities::health::{DamageType, Health, TakeDamage},
+        players::team::Factions,
+    },
 };
 
-use super::movement::Velocity;
+use super::{movement::Velocity, spatial::SpatialIndex};
 
 #[derive(Debug, Component, PartialEq, Serialize, Deserialize, Copy, Clone)]
-#[require(Replicated, Velocity, Transform, BoxCollider = projectile_collider(), Sprite, Anchor::BOTTOM_CENTER)]
+#[require(Replicated, Velocity, Transform, BoxCollider = projectile_collider(), Sprite, Anchor::BOTTOM_CENTER, ProjectileHits)]
 pub enum ProjectileType {
     Arrow,
+    FireArrow,
+    Bomb,
+    Boulder,
+}
+
+impl ProjectileType {
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            ProjectileType::Arrow | ProjectileType::Bomb | ProjectileType::Boulder => {
+                DamageType::Physical
+            }
+            ProjectileType::FireArrow => DamageType::Fire,
+        }
+    }
+
+    pub fn hit_by(&self) -> Hitby {
+        match self {
+            ProjectileType::Arrow | ProjectileType::FireArrow => Hitby::Arrow,
+            ProjectileType::Bomb | ProjectileType::Boulder => Hitby::Explosion,
+        }
+    }
+
+    /// Number of targets a projectile passes through before it is spent.
+    ///
+    /// Explosives burst on their first target instead, fire arrows lodge in it to set it alight.
+    pub fn pierce(&self) -> u8 {
+        match self {
+            ProjectileType::Arrow => 2,
+            ProjectileType::FireArrow | ProjectileType::Bomb | ProjectileType::Boulder => 1,
+        }
+    }
+
+    /// Explosives burst on impact and damage everything within this radius.
+    pub fn splash_radius(&self) -> Option<f32> {
+        match self {
+            ProjectileType::Arrow | ProjectileType::FireArrow => None,
+            ProjectileType::Bomb => Some(40.),
+            ProjectileType::Boulder => Some(25.),
+        }
+    }
+
+    /// Factor on the damage dealt to buildings.
+    pub fn building_multiplier(&self) -> f32 {
+        match self {
+            ProjectileType::Arrow | ProjectileType::FireArrow => 1.,
+            ProjectileType::Bomb => 2.5,
+            ProjectileType::Boulder => 4.,
+        }
+    }
+
+    /// Factor on the damage dealt to everything but buildings.
+    pub fn unit_multiplier(&self) -> f32 {
+        match self {
+            ProjectileType::Arrow | ProjectileType::FireArrow | ProjectileType::Bomb => 1.,
+            ProjectileType::Boulder => 0.3,
+        }
+    }
+
+    /// Damage dealt to a target this projectile reaches.
+    fn impact(
+        &self,
+        target_entity: Entity,
+        damage: f32,
+        delta_x: f32,
+        is_building: bool,
+        attacker: Option<Entity>,
+    ) -> TakeDamage {
+        let multiplier = match is_building {
+            true => self.building_multiplier(),
+            false => self.unit_multiplier(),
+        };
+
+        TakeDamage {
+            target_entity,
+            damage: damage * multiplier,
+            damage_type: self.damage_type(),
+            direction: delta_x.into(),
+            by: self.hit_by(),
+            attacker,
+        }
+    }
+}
+
+/// The unit that fired a projectile.
+#[derive(Component, Clone, Copy, Deref)]
+pub struct Shooter(pub Entity);
+
+/// Rules for what projectiles hit, set per game.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ProjectileSettings {
+    /// Projectiles also hit allies of the shooter, never the shooter itself.
+    pub friendly_fire: bool,
+    /// Seconds a projectile stays stuck in the ground before it disappears.
+    pub ground_duration: f32,
+    /// Seconds a projectile stays stuck in a building before it disappears.
+    pub building_duration: f32,
+}
+
+impl Default for ProjectileSettings {
+    fn default() -> Self {
+        Self {
+            friendly_fire: false,
+            ground_duration: 3.,
+            building_duration: 10.,
+        }
+    }
+}
+
+/// Targets a projectile already hit, and how many more it can pass through.
+#[derive(Component, Clone, Debug)]
+pub struct ProjectileHits {
+    remaining: u8,
+    hit: Vec<Entity>,
+}
+
+impl ProjectileHits {
+    /// A projectile that hits up to `pierce` targets before it is spent.
+    pub fn new(pierce: u8) -> Self {
+        Self {
+            remaining: pierce.max(1),
+            hit: Vec::new(),
+        }
+    }
+}
+
+impl Default for ProjectileHits {
+    fn default() -> Self {
+        Self::new(1)
+    }
 }
 
 pub struct ProjectilePlugin;
 
 impl Plugin for ProjectilePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(FixedUpdate, projectile_collision);
+        app.init_resource::<ProjectileSettings>()
+            .add_systems(FixedUpdate, projectile_collision);
     }
 }
 
-type TargetComponents<'a> = (Entity, &'a Transform, &'a BoxCollider, &'a Owner);
+type TargetComponents<'a> = (
+    Entity,
+    &'a Transform,
+    &'a BoxCollider,
+    &'a Owner,
+    Has<Building>,
+);
+
+/// Stops a projectile where it is and lets it disappear after `seconds`.
+fn stick(commands: &mut Commands, projectile: Entity, velocity: &mut Velocity, seconds: f32) {
+    velocity.0 = Vec2::ZERO;
+    commands
+        .entity(projectile)
+        .remove::<BoxCollider>()
+        .insert(DelayedDespawn(Timer::from_seconds(
+            seconds,
+            TimerMode::Once,
+        )));
+}
 
 #[allow(clippy::type_complexity)]
 fn projectile_collision(
     mut commands: Commands,
-    mut projectiles: Query<
-        (
-            Entity,
-            &Transform,
-            &mut Velocity,
-            &BoxCollider,
-            &Owner,
-            &Damage,
-        ),
-        With<ProjectileType>,
-    >,
+    mut projectiles: Query<(
+        Entity,
+        &Transform,
+        &mut Velocity,
+        &BoxCollider,
+        &Owner,
+        &Damage,
+        &GameSceneId,
+        Option<&Shooter>,
+        &ProjectileType,
+        &mut ProjectileHits,
+    )>,
     targets: Query<TargetComponents, (With<Health>, Without<ProjectileType>)>,
+    factions: Factions,
+    settings: Res<ProjectileSettings>,
+    index: Res<SpatialIndex>,
     mut attack_events: MessageWriter<TakeDamage>,
 ) {
-    for (entity, transform, mut velocity, collider, owner, damage) in &mut projectiles {
-        if transform.translation.y - collider.dimension.y <= 0.0 {
-            velocity.0 = Vec2::ZERO;
-            commands.entity(entity).remove::<BoxCollider>();
-            commands
-                .entity(entity)
-                .insert(DelayedDespawn(Timer::from_seconds(3., TimerMode::Once)));
-            continue;
-        }
+    for (
+        entity,
+        transform,
+        mut velocity,
+        collider,
+        owner,
+        damage,
+        game_scene_id,
+        shooter,
+        projectile_type,
+        mut hits,
+    ) in &mut projectiles
+    {
+        let grounded = transform.translation.y - collider.dimension.y <= 0.0;
+        let projectile = collider.at(transform);
+        let shooter = shooter.map(|shooter| **shooter);
+        let can_hit = |target_entity: Entity, target_owner: &Owner| {
+            Some(target_entity) != shooter
+                && (settings.friendly_fire || factions.is_enemy(owner, target_owner))
+        };
 
-        for (target_entity, target_transform, target_collider, target_owner) in targets.iter() {
-            if owner == target_owner {
+        // Explosives burst on the first target or the ground and damage everything around them.
+        if let Some(radius) = projectile_type.splash_radius() {
+            let touched = grounded
+                || index
+                    .overlapping(*game_scene_id, &projectile)
+                    .into_iter()
+                    .filter_map(|candidate| targets.get(candidate).ok())
+                    .any(
+                        |(target_entity, target_transform, target_collider, target_owner, _)| {
+                            can_hit(target_entity, target_owner)
+                                && projectile.intersects(&target_collider.at(target_transform))
+                        },
+                    );
+            if !touched {
                 continue;
             }
 
-            let projectile = collider.at(transform);
-            let target = target_collider.at(target_transform);
+            let center = transform.translation.x;
+            let caught = index
+                .near(*game_scene_id, center, radius)
+                .into_iter()
+                .filter_map(|candidate| targets.get(candidate).ok())
+                .filter(|(target_entity, _, _, target_owner, _)| {
+                    can_hit(*target_entity, target_owner)
+                })
+                .filter(|(_, target_transform, target_collider, ..)| {
+                    let bounds = target_collider.at(target_transform);
+                    (center.clamp(bounds.min.x, bounds.max.x) - center).abs() <= radius
+                });
+            for (target_entity, target_transform, _, _, is_building) in caught {
+                attack_events.write(projectile_type.impact(
+                    target_entity,
+                    **damage,
+                    target_transform.translation.x - center,
+                    is_building,
+                    shooter,
+                ));
+            }
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if grounded {
+            stick(
+                &mut commands,
+                entity,
+                &mut velocity,
+                settings.ground_duration,
+            );
+            continue;
+        }
 
-            if projectile.intersects(&target) {
-                let delta_x = target_transform.translation.x - transform.translation.x;
+        let mut struck: Vec<_> = index
+            .overlapping(*game_scene_id, &projectile)
+            .into_iter()
+            .filter_map(|candidate| targets.get(candidate).ok())
+            .filter(|(target_entity, _, _, target_owner, _)| {
+                !hits.hit.contains(target_entity) && can_hit(*target_entity, target_owner)
+            })
+            .filter(|(_, target_transform, target_collider, ..)| {
+                projectile.intersects(&target_collider.at(target_transform))
+            })
+            .collect();
 
-                attack_events.write(TakeDamage {
-                    target_entity,
-                    damage: **damage,
-                    direction: delta_x.into(),
-                    by: Hitby::Arrow,
-                });
+        // Several targets in one frame are hit in the order the projectile reaches them.
+        let heading = velocity.0.x.signum();
+        struck.sort_by(|(_, a, ..), (_, b, ..)| {
+            (a.translation.x * heading).total_cmp(&(b.translation.x * heading))
+        });
+
+        for (target_entity, target_transform, _, _, is_building) in struck {
+            let delta_x = target_transform.translation.x - transform.translation.x;
+
+            attack_events.write(projectile_type.impact(
+                target_entity,
+                **damage,
+                delta_x,
+                is_building,
+                shooter,
+            ));
+            hits.hit.push(target_entity);
+            hits.remaining -= 1;
+
+            // Buildings stop projectiles, they don't pierce through walls.
+            if is_building {
+                stick(
+                    &mut commands,
+                    entity,
+                    &mut velocity,
+                    settings.building_duration,
+                );
+                break;
+            }
+            if hits.remaining == 0 {
                 commands.entity(entity).despawn();
+                break;
             }
         }
     }
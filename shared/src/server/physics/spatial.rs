@@ -0,0 +1,127 @@
+use bevy::{math::bounding::Aabb2d, platform::collections::HashMap, prelude::*};
+
+use std::ops::RangeInclusive;
+
+use crate::{BoxCollider, GameSceneId};
+
+/// Width of a bucket along x, roughly the size of a building.
+const BUCKET_WIDTH: f32 = 128.;
+
+pub struct SpatialIndexPlugin;
+
+impl Plugin for SpatialIndexPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex>()
+            .add_systems(FixedPreUpdate, update_spatial_index);
+    }
+}
+
+type Bucket = (GameSceneId, i32);
+
+/// Entities with a [`GameSceneId`] sorted into buckets along x, per scene.
+///
+/// Entities are stored in every bucket their [`BoxCollider`] overlaps.
+/// Lookups return candidates, callers still do their exact distance or intersection check.
+#[derive(Resource, Default)]
+pub struct SpatialIndex {
+    buckets: HashMap<Bucket, Vec<Entity>>,
+    entries: HashMap<Entity, (GameSceneId, RangeInclusive<i32>)>,
+}
+
+impl SpatialIndex {
+    fn bucket(x: f32) -> i32 {
+        (x / BUCKET_WIDTH).floor() as i32
+    }
+
+    fn buckets(min_x: f32, max_x: f32) -> RangeInclusive<i32> {
+        Self::bucket(min_x)..=Self::bucket(max_x)
+    }
+
+    fn insert(&mut self, entity: Entity, scene: GameSceneId, range: RangeInclusive<i32>) {
+        if self
+            .entries
+            .get(&entity)
+            .is_some_and(|entry| *entry == (scene, range.clone()))
+        {
+            return;
+        }
+
+        self.remove(entity);
+        for bucket in range.clone() {
+            self.buckets
+                .entry((scene, bucket))
+                .or_default()
+                .push(entity);
+        }
+        self.entries.insert(entity, (scene, range));
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        let Some((scene, range)) = self.entries.remove(&entity) else {
+            return;
+        };
+
+        for bucket in range {
+            let Some(entities) = self.buckets.get_mut(&(scene, bucket)) else {
+                continue;
+            };
+            entities.retain(|other| *other != entity);
+            if entities.is_empty() {
+                self.buckets.remove(&(scene, bucket));
+            }
+        }
+    }
+
+    /// Entities of `scene` that may lie between `min_x` and `max_x`.
+    pub fn within(&self, scene: GameSceneId, min_x: f32, max_x: f32) -> Vec<Entity> {
+        let mut entities: Vec<Entity> = Self::buckets(min_x, max_x)
+            .filter_map(|bucket| self.buckets.get(&(scene, bucket)))
+            .flatten()
+            .copied()
+            .collect();
+
+        // Entities wider than a bucket are stored in more than one.
+        entities.sort_unstable();
+        entities.dedup();
+        entities
+    }
+
+    /// Entities of `scene` that may lie within `radius` of `x`.
+    pub fn near(&self, scene: GameSceneId, x: f32, radius: f32) -> Vec<Entity> {
+        self.within(scene, x - radius, x + radius)
+    }
+
+    /// Entities of `scene` whose collider may intersect `bounds`.
+    pub fn overlapping(&self, scene: GameSceneId, bounds: &Aabb2d) -> Vec<Entity> {
+        self.within(scene, bounds.min.x, bounds.max.x)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    query: Query<
+        (Entity, &Transform, &GameSceneId, Option<&BoxCollider>),
+        Or<(
+            Changed<Transform>,
+            Changed<GameSceneId>,
+            Changed<BoxCollider>,
+        )>,
+    >,
+    mut removed: RemovedComponents<GameSceneId>,
+) {
+    for entity in removed.read() {
+        index.remove(entity);
+    }
+
+    for (entity, transform, scene, collider) in query.iter() {
+        let range = match collider {
+            Some(collider) => {
+                let bounds = collider.at(transform);
+                SpatialIndex::buckets(bounds.min.x, bounds.max.x)
+            }
+            None => SpatialIndex::buckets(transform.translation.x, transform.translation.x),
+        };
+        index.insert(entity, *scene, range);
+    }
+}
@@ -5,13 +5,14 @@ use bevy_replicon::prelude::Replicated;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    BoxCollider, GameSceneId, Vec3LayerExt, map::Layers, networking::MountType,
-    server::physics::movement::Velocity, unit_collider,
+    BoxCollider, GameSceneId, networking::MountType, server::physics::movement::Velocity,
+    unit_collider,
 };
 
 use super::{
     interaction::{Interactable, InteractionTriggeredEvent, InteractionType},
     items::Item,
+    loot::spawn_item,
 };
 
 #[derive(Component, Clone, Serialize, Deserialize)]
@@ -67,17 +68,14 @@ pub fn open_chest(
             .remove::<Interactable>();
 
         let (chest_transform, game_scene_id) = query.get(event.interactable)?;
-        let chest_translation = chest_transform.translation;
 
         for _ in 0..3 {
-            let item = Item::random();
-            commands.spawn((
-                item.collider(),
-                item,
+            spawn_item(
+                &mut commands,
+                Item::random(),
+                chest_transform.translation,
                 *game_scene_id,
-                chest_translation.with_y(12.5).with_layer(Layers::Item),
-                Velocity(Vec2::new((fastrand::f32() - 0.5) * 50., 50.)),
-            ));
+            );
         }
     }
     Ok(())
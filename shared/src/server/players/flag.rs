@@ -13,14 +13,14 @@ use super::{
 
 #[derive(Message)]
 pub struct DropFlagEvent {
-    player: Entity,
-    flag: Entity,
+    pub player: Entity,
+    pub flag: Entity,
 }
 
 #[derive(Message)]
 pub struct PickFlagEvent {
-    player: Entity,
-    flag: Entity,
+    pub player: Entity,
+    pub flag: Entity,
 }
 
 #[derive(Component, Serialize, Deserialize)]
@@ -4,7 +4,10 @@ use bevy_replicon::prelude::*;
 use bevy::math::bounding::IntersectsVolume;
 use serde::{Deserialize, Serialize};
 
-use crate::{BoxCollider, ClientPlayerMap, ClientPlayerMapExt, PlayerState};
+use crate::{
+    BoxCollider, ClientPlayerMap, ClientPlayerMapExt, GameSceneId, PlayerState,
+    server::{physics::spatial::SpatialIndex, players::team::Factions},
+};
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum InteractionType {
@@ -13,12 +16,21 @@ pub enum InteractionType {
     Flag,
     ItemAssignment,
     Building,
+    SiegeEngine,
     Travel,
     Portal,
     Mount,
     Commander,
     Chest,
     Item,
+    Gold,
+}
+
+impl InteractionType {
+    /// Allies may use these even when they are restricted to another player.
+    pub fn shared_with_allies(&self) -> bool {
+        matches!(self, InteractionType::Building)
+    }
 }
 
 #[derive(Component, Clone, Copy, Debug, Serialize, Deserialize)]
@@ -75,19 +87,26 @@ fn send_interact(mut commands: Commands, keyboard_input: Res<ButtonInput<KeyCode
 fn interact(
     trigger: On<FromClient<Interact>>,
     mut triggered_events: MessageWriter<InteractionTriggeredEvent>,
-    players: Query<(&Transform, &BoxCollider)>,
+    players: Query<(&Transform, &BoxCollider, &GameSceneId)>,
     interactables: Query<(Entity, &Transform, &BoxCollider, &Interactable)>,
     client_player_map: Res<ClientPlayerMap>,
+    factions: Factions,
+    index: Res<SpatialIndex>,
 ) -> Result {
     let player = *client_player_map.get_player(&trigger.client_id)?;
-    let (player_transform, player_collider) = players.get(player)?;
+    let (player_transform, player_collider, game_scene_id) = players.get(player)?;
 
     let player_bounds = player_collider.at(player_transform);
 
-    let priority_interaction = interactables
-        .iter()
+    let priority_interaction = index
+        .overlapping(*game_scene_id, &player_bounds)
+        .into_iter()
+        .filter_map(|candidate| interactables.get(candidate).ok())
         .filter(|(.., transform, collider, _)| player_bounds.intersects(&collider.at(transform)))
         .filter(|(.., interactable)| match interactable.restricted_to {
+            Some(owner) if interactable.kind.shared_with_allies() => {
+                factions.shares_with(owner, player)
+            }
             Some(owner) => owner.eq(&player),
             None => true,
         })
@@ -8,7 +8,10 @@ use crate::{
     BoxCollider,
     enum_map::*,
     networking::{Inventory, UnitType},
-    server::{buildings::item_assignment::ItemSlot, physics::movement::Velocity},
+    server::{
+        buildings::item_assignment::ItemSlot,
+        physics::{movement::Velocity, projectile::ProjectileType},
+    },
 };
 
 use super::interaction::{Interactable, InteractionTriggeredEvent, InteractionType};
@@ -110,10 +113,14 @@ impl Item {
                     },
                 },
                 WeaponType::Projectile(projectile_weapon) => match projectile_weapon {
-                    ProjectileWeapon::Bow => BoxCollider {
+                    ProjectileWeapon::Bow | ProjectileWeapon::FireBow => BoxCollider {
                         dimension: Vec2::new(5., 12.),
                         offset: None,
                     },
+                    ProjectileWeapon::Bombs => BoxCollider {
+                        dimension: Vec2::new(8., 8.),
+                        offset: None,
+                    },
                 },
             },
             ItemType::Chest => BoxCollider {
@@ -249,10 +256,13 @@ pub enum Effect {
     Health,
     MeleeRange(WeaponType),
     ProjectileRange(WeaponType),
+    Accuracy(WeaponType),
     AttackSpeed,
     MovementSpeed,
     UnitAmount,
     Sight,
+    Armor,
+    MagicResist,
 }
 
 impl Effect {
@@ -266,19 +276,32 @@ impl Effect {
                     MeleeWeapon::Pike => 40..=50,
                 },
                 WeaponType::Projectile(projectile) => match projectile {
-                    ProjectileWeapon::Bow => 10..=15,
+                    ProjectileWeapon::Bow | ProjectileWeapon::FireBow => 10..=15,
+                    ProjectileWeapon::Bombs => 15..=20,
                 },
             },
             Effect::ProjectileRange(weapon) => match weapon {
                 WeaponType::Melee(_) => 0..=0,
                 WeaponType::Projectile(projectile) => match projectile {
                     ProjectileWeapon::Bow => 240..=280,
+                    ProjectileWeapon::FireBow => 220..=260,
+                    ProjectileWeapon::Bombs => 140..=180,
+                },
+            },
+            Effect::Accuracy(weapon) => match weapon {
+                WeaponType::Melee(_) => 0..=0,
+                WeaponType::Projectile(projectile) => match projectile {
+                    ProjectileWeapon::Bow => 60..=80,
+                    ProjectileWeapon::FireBow => 55..=75,
+                    ProjectileWeapon::Bombs => 40..=60,
                 },
             },
             Effect::AttackSpeed => 10..=12,
             Effect::MovementSpeed => 25..=45,
             Effect::UnitAmount => 4..=4,
             Effect::Sight => 290..=310,
+            Effect::Armor => 20..=40,
+            Effect::MagicResist => 20..=40,
         };
         let amount = fastrand::i32(range);
         BaseEffect {
@@ -313,10 +336,13 @@ impl fmt::Display for Effect {
             Effect::Health => "Health",
             Effect::MeleeRange(_) => "MeleeRange",
             Effect::ProjectileRange(_) => "ProjectileRange",
+            Effect::Accuracy(_) => "Accuracy",
             Effect::AttackSpeed => "AttackSpeed",
             Effect::MovementSpeed => "MovementSpeed",
             Effect::UnitAmount => "UnitAmount",
             Effect::Sight => "SightRange",
+            Effect::Armor => "Armor",
+            Effect::MagicResist => "MagicResist",
         };
         write!(f, "{s}")
     }
@@ -364,9 +390,13 @@ impl ItemType {
                     Effect::AttackSpeed,
                     Effect::MeleeRange(*weapon),
                     Effect::ProjectileRange(*weapon),
+                    Effect::Accuracy(*weapon),
                 ]
             }
-            ItemType::Chest => vec![Effect::Health],
+            ItemType::Chest => {
+                let armor = *fastrand::choice(&[Effect::Armor, Effect::MagicResist]).unwrap();
+                vec![Effect::Health, armor]
+            }
             ItemType::Feet => vec![Effect::MovementSpeed],
             ItemType::Head => vec![Effect::UnitAmount, Effect::Sight],
         };
@@ -386,10 +416,19 @@ impl ItemType {
             effects.push(Effect::MeleeRange(*weapon_type));
         }
 
+        if let ItemType::Weapon(weapon_type @ WeaponType::Projectile(_)) = self {
+            effects.push(Effect::Accuracy(*weapon_type));
+        }
+
         if let ItemType::Head = self {
             effects.push(Effect::Sight);
         }
 
+        if let ItemType::Chest = self {
+            effects.push(Effect::Armor);
+            effects.push(Effect::MagicResist);
+        }
+
         let effect = fastrand::choice(effects).unwrap();
         effect.multiplier(amplitude, sign)
     }
@@ -428,6 +467,18 @@ pub enum MeleeWeapon {
 #[derive(Clone, Serialize, Deserialize, Copy, Mappable, Debug, Eq, PartialEq)]
 pub enum ProjectileWeapon {
     Bow,
+    FireBow,
+    Bombs,
+}
+
+impl ProjectileWeapon {
+    pub fn projectile(&self) -> ProjectileType {
+        match self {
+            ProjectileWeapon::Bow => ProjectileType::Arrow,
+            ProjectileWeapon::FireBow => ProjectileType::FireArrow,
+            ProjectileWeapon::Bombs => ProjectileType::Bomb,
+        }
+    }
 }
 
 impl WeaponType {
@@ -438,7 +489,9 @@ impl WeaponType {
                 MeleeWeapon::Pike => UnitType::Pikeman,
             },
             WeaponType::Projectile(projectile_weapon) => match projectile_weapon {
-                ProjectileWeapon::Bow => UnitType::Archer,
+                ProjectileWeapon::Bow | ProjectileWeapon::FireBow | ProjectileWeapon::Bombs => {
+                    UnitType::Archer
+                }
             },
         }
     }
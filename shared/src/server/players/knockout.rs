@@ -7,14 +7,16 @@ use crate::{
         Layers,
         buildings::{Building, BuildingType},
     },
+    networking::Inventory,
     server::{
         ai::{Target, TargetedBy},
         buildings::recruiting::{Flag, FlagHolder},
         entities::{
             Unit,
-            health::{Health, TakeDamage},
+            health::{DamageApplied, Health},
         },
         physics::{attachment::AttachedTo, movement::Velocity},
+        players::loot::{LootTables, spawn_gold},
     },
 };
 
@@ -44,13 +46,15 @@ impl Plugin for KnockoutPlugin {
 }
 
 fn kill_player(
-    mut damage_events: MessageReader<TakeDamage>,
+    mut damage_events: MessageReader<DamageApplied>,
     mut player: Query<
         (
             Entity,
             &mut Transform,
             &Owner,
             &Health,
+            &mut Inventory,
+            &GameSceneId,
             Option<&TargetedBy>,
             Option<&FlagHolder>,
         ),
@@ -63,14 +67,20 @@ fn kill_player(
     mut next_state: ResMut<NextState<PlayerState>>,
     mut king_animation: MessageWriter<ToClients<AnimationChangeEvent>>,
     transform: Query<&Transform, (With<Flag>, Without<Player>)>,
+    loot: Res<LootTables>,
     mut commands: Commands,
 ) -> Result {
+    // Several hits in one tick knock a king out only once.
+    let mut knocked_out = Vec::new();
+
     for damage_event in damage_events.read() {
         let Ok((
             player_entity,
             mut player_transform,
             player,
             player_health,
+            mut inventory,
+            game_scene_id,
             maybe_targeted_by,
             maybe_flag_holder,
         )) = player.get_mut(damage_event.target_entity)
@@ -78,9 +88,10 @@ fn kill_player(
             continue;
         };
 
-        if player_health.hitpoints > 0. {
+        if player_health.hitpoints > 0. || knocked_out.contains(&player_entity) {
             continue;
         }
+        knocked_out.push(player_entity);
 
         if let Some(targeted_by) = maybe_targeted_by {
             commands
@@ -96,6 +107,15 @@ fn kill_player(
             },
         });
 
+        let dropped = (inventory.gold as f32 * loot.king_gold_share.clamp(0., 1.)) as u16;
+        inventory.gold -= dropped;
+        spawn_gold(
+            &mut commands,
+            dropped,
+            player_transform.translation,
+            *game_scene_id,
+        );
+
         if let Some(flag) = maybe_flag_holder {
             let flag_transform = transform.get(**flag)?;
 
@@ -109,7 +129,7 @@ fn kill_player(
 
         for (building_transform, owner, building, building_scene) in main_building.iter() {
             if let BuildingType::MainBuilding { level: _ } = building.building_type
-                && owner.is_same_faction(player)
+                && owner == player
             {
                 commands
                     .entity(player_entity)
@@ -0,0 +1,269 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use std::ops::RangeInclusive;
+
+use bevy_replicon::prelude::{Replicated, SendMode, ServerTriggerExt, ToClients};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BoxCollider, GameSceneId, Owner, Player, Vec3LayerExt,
+    map::{
+        Layers,
+        buildings::{BuildStatus, Building},
+    },
+    networking::Inventory,
+    server::{
+        entities::health::{DamageApplied, UnitDied},
+        physics::movement::Velocity,
+        stats::{GoldReason, GoldTransaction},
+    },
+};
+
+use super::{
+    interaction::{Interactable, InteractableSound, InteractionTriggeredEvent, InteractionType},
+    items::Item,
+    team::Factions,
+};
+
+/// What a defeated enemy leaves behind.
+#[derive(Debug, Clone)]
+pub struct DropTable {
+    /// Chance from 0 to 1 of dropping gold.
+    pub gold_chance: f32,
+    pub gold: RangeInclusive<u16>,
+    /// Chance from 0 to 1 of dropping a random item.
+    pub item_chance: f32,
+}
+
+impl DropTable {
+    fn drop(&self, commands: &mut Commands, translation: Vec3, game_scene_id: GameSceneId) {
+        if fastrand::f32() < self.gold_chance {
+            let amount = fastrand::u16(self.gold.clone());
+            spawn_gold(commands, amount, translation, game_scene_id);
+        }
+        if fastrand::f32() < self.item_chance {
+            spawn_item(commands, Item::random(), translation, game_scene_id);
+        }
+    }
+}
+
+/// Drop tables, set per game.
+#[derive(Resource, Debug, Clone)]
+pub struct LootTables {
+    pub bandit: DropTable,
+    /// Dropped by buildings once they are destroyed.
+    pub building: DropTable,
+    /// Share of their gold knocked out kings drop, from 0 to 1.
+    pub king_gold_share: f32,
+}
+
+impl Default for LootTables {
+    fn default() -> Self {
+        Self {
+            bandit: DropTable {
+                gold_chance: 0.5,
+                gold: 5..=20,
+                item_chance: 0.1,
+            },
+            building: DropTable {
+                gold_chance: 1.,
+                gold: 20..=60,
+                item_chance: 0.3,
+            },
+            king_gold_share: 0.25,
+        }
+    }
+}
+
+/// Gold lying on the ground, any player can pick it up.
+#[derive(Component, Clone, Copy, Debug, Deref, Serialize, Deserialize)]
+#[require(
+    Replicated,
+    Transform,
+    BoxCollider = gold_collider(),
+    Velocity,
+    Sprite,
+    Interactable{
+        kind: InteractionType::Gold,
+        restricted_to: None,
+    },
+)]
+pub struct GoldPile(pub u16);
+
+pub struct LootPlugin;
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LootTables>()
+            .add_observer(drop_bandit_loot)
+            .add_systems(
+                FixedUpdate,
+                (
+                    pickup_gold.run_if(on_message::<InteractionTriggeredEvent>),
+                    drop_building_loot,
+                ),
+            );
+    }
+}
+
+/// Tosses loot up, so drops scatter a little before they land.
+fn toss() -> Velocity {
+    Velocity(Vec2::new((fastrand::f32() - 0.5) * 50., 50.))
+}
+
+pub fn spawn_gold(
+    commands: &mut Commands,
+    amount: u16,
+    translation: Vec3,
+    game_scene_id: GameSceneId,
+) {
+    if amount == 0 {
+        return;
+    }
+
+    commands.spawn((
+        GoldPile(amount),
+        game_scene_id,
+        translation.with_y(12.5).with_layer(Layers::Item),
+        toss(),
+    ));
+}
+
+pub fn spawn_item(
+    commands: &mut Commands,
+    item: Item,
+    translation: Vec3,
+    game_scene_id: GameSceneId,
+) {
+    commands.spawn((
+        item.collider(),
+        item,
+        game_scene_id,
+        translation.with_y(12.5).with_layer(Layers::Item),
+        toss(),
+    ));
+}
+
+fn drop_bandit_loot(
+    trigger: On<UnitDied>,
+    units: Query<(&Transform, &GameSceneId)>,
+    loot: Res<LootTables>,
+    mut commands: Commands,
+) -> Result {
+    let UnitDied { entity, owner, .. } = *trigger.event();
+    let Owner::Bandits = owner else {
+        return Ok(());
+    };
+
+    let (transform, game_scene_id) = units.get(entity)?;
+    loot.bandit
+        .drop(&mut commands, transform.translation, *game_scene_id);
+    Ok(())
+}
+
+/// Owner of whoever last damaged a building.
+#[derive(Component, Clone, Copy, Deref)]
+struct LastAttacker(Owner);
+
+/// Buildings only drop loot when an enemy destroyed them, not when allies tear them down.
+fn drop_building_loot(
+    mut damage: MessageReader<DamageApplied>,
+    attackers: Query<(Option<&Owner>, Has<Player>)>,
+    buildings: Query<
+        (
+            Entity,
+            &Transform,
+            &BuildStatus,
+            &Owner,
+            &GameSceneId,
+            Option<&LastAttacker>,
+        ),
+        (With<Building>, Changed<BuildStatus>),
+    >,
+    targets: Query<(), With<Building>>,
+    factions: Factions,
+    loot: Res<LootTables>,
+    mut commands: Commands,
+) {
+    // Hits of this tick aren't on the buildings yet, the commands inserting them run later.
+    let mut hit_by = HashMap::new();
+
+    for event in damage.read() {
+        if !targets.contains(event.target_entity) {
+            continue;
+        }
+        let attacker = event
+            .attacker
+            .and_then(|attacker| match attackers.get(attacker).ok()? {
+                (_, true) => Some(Owner::Player(attacker)),
+                (owner, false) => owner.copied(),
+            });
+        let mut building = commands.entity(event.target_entity);
+        match attacker {
+            Some(attacker) => building.try_insert(LastAttacker(attacker)),
+            None => building.try_remove::<LastAttacker>(),
+        };
+        hit_by.insert(event.target_entity, attacker);
+    }
+
+    for (entity, transform, status, owner, game_scene_id, last_attacker) in buildings.iter() {
+        let BuildStatus::Destroyed = status else {
+            continue;
+        };
+        // A rebuilt building starts without a grudge.
+        commands.entity(entity).try_remove::<LastAttacker>();
+
+        let attacker = match hit_by.get(&entity) {
+            Some(attacker) => *attacker,
+            None => last_attacker.map(|last_attacker| **last_attacker),
+        };
+        let Some(attacker) = attacker else {
+            continue;
+        };
+        if !factions.is_enemy(&attacker, owner) {
+            continue;
+        }
+        loot.building
+            .drop(&mut commands, transform.translation, *game_scene_id);
+    }
+}
+
+fn pickup_gold(
+    mut interactions: MessageReader<InteractionTriggeredEvent>,
+    mut players: Query<&mut Inventory>,
+    piles: Query<(&GoldPile, &Transform)>,
+    mut gold: MessageWriter<GoldTransaction>,
+    mut commands: Commands,
+) -> Result {
+    for event in interactions.read() {
+        let InteractionType::Gold = &event.interaction else {
+            continue;
+        };
+
+        let (pile, transform) = piles.get(event.interactable)?;
+        let mut inventory = players.get_mut(event.player)?;
+        inventory.gold = inventory.gold.saturating_add(**pile);
+        gold.write(GoldTransaction::earned(
+            event.player,
+            **pile,
+            GoldReason::Loot,
+        ));
+
+        commands.entity(event.interactable).despawn();
+        commands.server_trigger(ToClients {
+            mode: SendMode::Broadcast,
+            message: InteractableSound {
+                kind: InteractionType::Gold,
+                spatial_position: transform.translation,
+            },
+        });
+    }
+    Ok(())
+}
+
+fn gold_collider() -> BoxCollider {
+    BoxCollider {
+        dimension: Vec2::new(10., 6.),
+        offset: None,
+    }
+}
@@ -4,6 +4,7 @@ use chest::open_chest;
 use flag::{DropFlagEvent, PickFlagEvent, drop_flag, flag_interact, pick_flag};
 use interaction::InteractionTriggeredEvent;
 use items::pickup_item;
+use loot::LootPlugin;
 use mount::MountPlugin;
 
 use crate::server::players::knockout::KnockoutPlugin;
@@ -13,13 +14,15 @@ pub mod flag;
 pub mod interaction;
 pub mod items;
 pub mod knockout;
+pub mod loot;
 pub mod mount;
+pub mod team;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((MountPlugin, KnockoutPlugin))
+        app.add_plugins((MountPlugin, KnockoutPlugin, LootPlugin))
             .add_message::<DropFlagEvent>()
             .add_message::<PickFlagEvent>()
             .add_systems(
@@ -0,0 +1,241 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_replicon::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ClientPlayerMap, ClientPlayerMapExt, GameSceneId, Owner, Player, PlayerColor, PlayerState,
+    networking::LobbyMessage,
+};
+
+/// Number of teams players can pick from in the lobby.
+const LOBBY_TEAMS: u8 = 4;
+
+/// Kings have to stand this close to propose an alliance.
+const ALLIANCE_RANGE: f32 = 100.;
+
+/// Seconds an alliance proposal waits for the other king to answer.
+const PROPOSAL_DURATION: f32 = 30.;
+
+/// Players of the same team are allies, players without a team only fight for themselves.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Team(pub u8);
+
+/// Decides who is friend and who is foe, taking teams into account.
+#[derive(SystemParam)]
+pub struct Factions<'w, 's> {
+    teams: Query<'w, 's, &'static Team>,
+}
+
+impl Factions<'_, '_> {
+    pub fn is_allied(&self, owner: &Owner, other: &Owner) -> bool {
+        match (owner, other) {
+            (Owner::Bandits, Owner::Bandits) => true,
+            (Owner::Player(player), Owner::Player(other)) => {
+                player == other
+                    || matches!(
+                        (self.teams.get(*player), self.teams.get(*other)),
+                        (Ok(team), Ok(other_team)) if team == other_team
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_enemy(&self, owner: &Owner, other: &Owner) -> bool {
+        !self.is_allied(owner, other)
+    }
+
+    /// Whether `player` may use something restricted to `owner`.
+    pub fn shares_with(&self, owner: Entity, player: Entity) -> bool {
+        self.is_allied(&Owner::Player(owner), &Owner::Player(player))
+    }
+}
+
+/// Asks the nearest king to form an alliance, it is formed once both kings asked each other.
+#[derive(Event, Serialize, Deserialize, Debug)]
+struct ProposeAlliance;
+
+/// Tells a king about alliances other kings propose to or form with them.
+#[derive(Event, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum AllianceNotice {
+    Proposed { by: PlayerColor },
+    Formed { with: PlayerColor },
+}
+
+#[derive(Component)]
+struct AllianceProposal {
+    to: Entity,
+    timer: Timer,
+}
+
+pub struct TeamPlugin;
+
+impl Plugin for TeamPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_client_event::<ProposeAlliance>(Channel::Ordered)
+            .add_server_event::<AllianceNotice>(Channel::Ordered)
+            .add_observer(propose_alliance)
+            .add_systems(
+                PostUpdate,
+                send_alliance_proposal
+                    .before(ClientSystems::Send)
+                    .run_if(in_state(PlayerState::World)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (lobby_teams, expire_proposals).run_if(in_state(ClientState::Disconnected)),
+            );
+    }
+}
+
+fn send_alliance_proposal(mut commands: Commands, keyboard_input: Res<ButtonInput<KeyCode>>) {
+    if keyboard_input.just_pressed(KeyCode::KeyL) {
+        commands.client_trigger(ProposeAlliance);
+    }
+}
+
+/// Cycles the sender through the lobby teams, and back to playing alone.
+fn lobby_teams(
+    mut lobby_events: MessageReader<FromClient<LobbyMessage>>,
+    players: Query<(&GameSceneId, Option<&Team>), With<Player>>,
+    client_player_map: Res<ClientPlayerMap>,
+    mut commands: Commands,
+) -> Result {
+    for FromClient { client_id, message } in lobby_events.read() {
+        let LobbyMessage::CycleTeam = message else {
+            continue;
+        };
+        let player = *client_player_map.get_player(client_id)?;
+        let (game_scene_id, team) = players.get(player)?;
+        if *game_scene_id != GameSceneId::lobby() {
+            continue;
+        }
+
+        match team {
+            Some(Team(team)) if *team >= LOBBY_TEAMS => {
+                commands.entity(player).remove::<Team>();
+            }
+            Some(Team(team)) => {
+                commands.entity(player).insert(Team(team + 1));
+            }
+            None => {
+                commands.entity(player).insert(Team(1));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn propose_alliance(
+    trigger: On<FromClient<ProposeAlliance>>,
+    players: Query<(Entity, &Player, &Transform, &GameSceneId, Option<&Team>)>,
+    proposals: Query<&AllianceProposal>,
+    factions: Factions,
+    client_player_map: Res<ClientPlayerMap>,
+    mut commands: Commands,
+) -> Result {
+    let player = *client_player_map.get_player(&trigger.client_id)?;
+    let (_, proposer, transform, game_scene_id, team) = players.get(player)?;
+
+    let nearest = players
+        .iter()
+        .filter(|(other, _, _, other_scene_id, _)| {
+            other_scene_id == &game_scene_id && !factions.shares_with(*other, player)
+        })
+        .map(|(other, other_player, other_transform, _, other_team)| {
+            let distance = (other_transform.translation.x - transform.translation.x).abs();
+            (other, other_player, distance, other_team)
+        })
+        .filter(|(_, _, distance, _)| *distance <= ALLIANCE_RANGE)
+        .min_by(|(_, _, a, _), (_, _, b, _)| a.total_cmp(b));
+    let Some((other, other_player, _, other_team)) = nearest else {
+        return Ok(());
+    };
+
+    let accepted = proposals
+        .get(other)
+        .is_ok_and(|proposal| proposal.to == player);
+    if !accepted {
+        commands.entity(player).insert(AllianceProposal {
+            to: other,
+            timer: Timer::from_seconds(PROPOSAL_DURATION, TimerMode::Once),
+        });
+        info!("{:?} proposes an alliance to {:?}.", player, other);
+        notify(
+            &mut commands,
+            &client_player_map,
+            other,
+            AllianceNotice::Proposed { by: proposer.color },
+        );
+        return Ok(());
+    }
+
+    // Both teams merge into the one that was proposed to, or a new team if neither has one.
+    let team = other_team.or(team).copied().unwrap_or_else(|| {
+        let highest = players
+            .iter()
+            .filter_map(|(.., team)| team.map(|team| team.0))
+            .max()
+            .unwrap_or(0);
+        Team(highest.max(LOBBY_TEAMS) + 1)
+    });
+    let members = players
+        .iter()
+        .filter(|(member, ..)| {
+            factions.shares_with(*member, player) || factions.shares_with(*member, other)
+        })
+        .map(|(member, ..)| member);
+    for member in members {
+        commands.entity(member).insert(team);
+    }
+    commands.entity(other).try_remove::<AllianceProposal>();
+    info!(
+        "{:?} and {:?} formed an alliance as {:?}.",
+        player, other, team
+    );
+    notify(
+        &mut commands,
+        &client_player_map,
+        player,
+        AllianceNotice::Formed {
+            with: other_player.color,
+        },
+    );
+    notify(
+        &mut commands,
+        &client_player_map,
+        other,
+        AllianceNotice::Formed {
+            with: proposer.color,
+        },
+    );
+    Ok(())
+}
+
+/// Rivals have no client and are left out.
+fn notify(
+    commands: &mut Commands,
+    client_player_map: &ClientPlayerMap,
+    player: Entity,
+    notice: AllianceNotice,
+) {
+    if let Ok(client) = client_player_map.get_network_entity(&player) {
+        commands.server_trigger(ToClients {
+            mode: SendMode::Direct(*client),
+            message: notice,
+        });
+    }
+}
+
+fn expire_proposals(
+    mut proposals: Query<(Entity, &mut AllianceProposal)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut proposal) in proposals.iter_mut() {
+        if proposal.timer.tick(time.delta()).just_finished() {
+            commands.entity(entity).remove::<AllianceProposal>();
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy_replicon::prelude::{ClientState, SendMode, ServerTriggerExt, ToClients};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GameState, Owner, Player,
+    map::buildings::{BuildStatus, Building, BuildingType},
+    server::{
+        entities::{
+            Unit,
+            health::{DamageApplied, UnitDied},
+        },
+        players::{
+            interaction::{InteractionTriggeredEvent, InteractionType},
+            team::Factions,
+        },
+    },
+};
+
+/// Directory the statistics of finished matches are exported to.
+const EXPORT_DIR: &str = "match_stats";
+
+/// Movement of the king above this within one tick is a teleport, not travel.
+const MAX_STEP: f32 = 50.;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MatchStats>()
+            .add_message::<GoldTransaction>()
+            .add_observer(record_deaths)
+            .add_systems(
+                OnTransition {
+                    exited: GameState::MainMenu,
+                    entered: GameState::GameSession,
+                },
+                reset_stats,
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    record_damage,
+                    record_gold,
+                    record_chests,
+                    record_destroyed_buildings,
+                    record_distance,
+                    end_match,
+                )
+                    .chain()
+                    .run_if(in_state(ClientState::Disconnected))
+                    .run_if(in_state(GameState::GameSession)),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoldReason {
+    GoldFarm,
+    Loot,
+    Recruitment,
+    Respawn,
+    Building,
+}
+
+/// Gold a player earned or spent, positive amounts are earnings.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct GoldTransaction {
+    pub player: Entity,
+    pub amount: i32,
+    pub reason: GoldReason,
+}
+
+impl GoldTransaction {
+    pub fn earned(player: Entity, amount: u16, reason: GoldReason) -> Self {
+        Self {
+            player,
+            amount: amount as i32,
+            reason,
+        }
+    }
+
+    pub fn spent(player: Entity, amount: u16, reason: GoldReason) -> Self {
+        Self {
+            player,
+            amount: -(amount as i32),
+            reason,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub id: u64,
+    pub color: String,
+    /// Damage dealt by the player's units, keyed by the dealing unit type.
+    ///
+    /// Counted before armor, shields and flanking, so it reflects what was swung rather than
+    /// what landed.
+    pub damage_dealt: BTreeMap<String, f32>,
+    /// Damage taken by the player's units, keyed by the receiving unit type.
+    pub damage_taken: BTreeMap<String, f32>,
+    pub kills: u32,
+    pub losses: u32,
+    pub buildings_destroyed: u32,
+    pub gold_earned: u32,
+    pub gold_spent_recruitment: u32,
+    pub gold_spent_respawn: u32,
+    pub gold_spent_buildings: u32,
+    pub chests_opened: u32,
+    pub distance_traveled: f32,
+}
+
+/// Statistics of all players over a whole match, sent to clients when it ends.
+#[derive(Event, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MatchSummary {
+    /// Seconds the match lasted.
+    pub elapsed: f32,
+    pub players: Vec<PlayerStats>,
+}
+
+#[derive(Resource, Default)]
+pub struct MatchStats {
+    elapsed: f32,
+    players: HashMap<Entity, PlayerStats>,
+    /// The player whose units hit a unit or building last, credited with its death.
+    last_attacker: HashMap<Entity, Entity>,
+    last_position: HashMap<Entity, f32>,
+    ended: bool,
+}
+
+impl MatchStats {
+    pub fn summary(&self) -> MatchSummary {
+        let mut players: Vec<PlayerStats> = self.players.values().cloned().collect();
+        players.sort_by_key(|player| player.id);
+
+        MatchSummary {
+            elapsed: self.elapsed,
+            players,
+        }
+    }
+
+    fn player(&mut self, entity: Entity, player: &Player) -> &mut PlayerStats {
+        self.players.entry(entity).or_insert_with(|| PlayerStats {
+            id: player.id,
+            color: format!("{:?}", player.color).to_lowercase(),
+            ..default()
+        })
+    }
+}
+
+/// Player an entity fights for, the player itself for kings.
+fn owning_player(entity: Entity, owners: &Query<(Option<&Owner>, Has<Player>)>) -> Option<Entity> {
+    match owners.get(entity).ok()? {
+        (_, true) => Some(entity),
+        (Some(Owner::Player(player)), false) => Some(*player),
+        _ => None,
+    }
+}
+
+/// Name a unit is listed under in the statistics.
+fn unit_kind(entity: Entity, kinds: &Query<(Option<&Unit>, Has<Player>, Has<Building>)>) -> String {
+    match kinds.get(entity) {
+        Ok((Some(unit), ..)) => format!("{:?}", unit.unit_type),
+        Ok((None, true, _)) => "King".to_string(),
+        Ok((None, _, true)) => "Building".to_string(),
+        _ => "Other".to_string(),
+    }
+}
+
+/// Starts every match with fresh statistics, resuming a paused match keeps them.
+fn reset_stats(mut stats: ResMut<MatchStats>) {
+    *stats = MatchStats::default();
+}
+
+fn record_damage(
+    mut damage: MessageReader<DamageApplied>,
+    mut stats: ResMut<MatchStats>,
+    owners: Query<(Option<&Owner>, Has<Player>)>,
+    kinds: Query<(Option<&Unit>, Has<Player>, Has<Building>)>,
+    players: Query<&Player>,
+) {
+    for event in damage.read() {
+        let victim = owning_player(event.target_entity, &owners);
+
+        if let Some(victim) = victim
+            && let Ok(player) = players.get(victim)
+        {
+            let kind = unit_kind(event.target_entity, &kinds);
+            *stats
+                .player(victim, player)
+                .damage_taken
+                .entry(kind)
+                .or_default() += event.damage;
+        }
+
+        let attacker = event
+            .attacker
+            .and_then(|attacker| Some((attacker, owning_player(attacker, &owners)?)));
+        let Some((attacker, attacking_player)) = attacker else {
+            // Bandits finishing off a unit don't leave the kill to the last player who hit it.
+            stats.last_attacker.remove(&event.target_entity);
+            continue;
+        };
+        if victim == Some(attacking_player) {
+            continue;
+        }
+        let Ok(player) = players.get(attacking_player) else {
+            continue;
+        };
+
+        let kind = unit_kind(attacker, &kinds);
+        *stats
+            .player(attacking_player, player)
+            .damage_dealt
+            .entry(kind)
+            .or_default() += event.damage;
+        stats
+            .last_attacker
+            .insert(event.target_entity, attacking_player);
+    }
+}
+
+fn record_deaths(trigger: On<UnitDied>, mut stats: ResMut<MatchStats>, players: Query<&Player>) {
+    let UnitDied { entity, owner, .. } = *trigger.event();
+
+    if let Owner::Player(owner) = owner
+        && let Ok(player) = players.get(owner)
+    {
+        stats.player(owner, player).losses += 1;
+    }
+
+    if let Some(killer) = stats.last_attacker.remove(&entity)
+        && let Ok(player) = players.get(killer)
+    {
+        stats.player(killer, player).kills += 1;
+    }
+}
+
+fn record_gold(
+    mut transactions: MessageReader<GoldTransaction>,
+    mut stats: ResMut<MatchStats>,
+    players: Query<&Player>,
+) {
+    for transaction in transactions.read() {
+        let Ok(player) = players.get(transaction.player) else {
+            continue;
+        };
+        let stats = stats.player(transaction.player, player);
+        let amount = transaction.amount.unsigned_abs();
+
+        match transaction.reason {
+            GoldReason::GoldFarm | GoldReason::Loot => stats.gold_earned += amount,
+            GoldReason::Recruitment => stats.gold_spent_recruitment += amount,
+            GoldReason::Respawn => stats.gold_spent_respawn += amount,
+            GoldReason::Building => stats.gold_spent_buildings += amount,
+        }
+    }
+}
+
+fn record_chests(
+    mut interactions: MessageReader<InteractionTriggeredEvent>,
+    mut stats: ResMut<MatchStats>,
+    players: Query<&Player>,
+) {
+    for event in interactions.read() {
+        let InteractionType::Chest = event.interaction else {
+            continue;
+        };
+        let Ok(player) = players.get(event.player) else {
+            continue;
+        };
+        stats.player(event.player, player).chests_opened += 1;
+    }
+}
+
+fn record_destroyed_buildings(
+    buildings: Query<(Entity, &BuildStatus), (With<Building>, Changed<BuildStatus>)>,
+    mut stats: ResMut<MatchStats>,
+    players: Query<&Player>,
+) {
+    for (entity, status) in buildings.iter() {
+        let BuildStatus::Destroyed = status else {
+            continue;
+        };
+        let Some(destroyer) = stats.last_attacker.remove(&entity) else {
+            continue;
+        };
+        let Ok(player) = players.get(destroyer) else {
+            continue;
+        };
+        stats.player(destroyer, player).buildings_destroyed += 1;
+    }
+}
+
+fn record_distance(
+    players: Query<(Entity, &Player, &Transform)>,
+    mut stats: ResMut<MatchStats>,
+    time: Res<Time>,
+) {
+    stats.elapsed += time.delta_secs();
+
+    for (entity, player, transform) in players.iter() {
+        let x = transform.translation.x;
+        let Some(last_x) = stats.last_position.insert(entity, x) else {
+            continue;
+        };
+
+        let step = (x - last_x).abs();
+        if step < MAX_STEP {
+            stats.player(entity, player).distance_traveled += step;
+        }
+    }
+}
+
+/// The match is over once only allies are left with a standing main building.
+fn end_match(
+    buildings: Query<(&Building, &BuildStatus, &Owner)>,
+    players: Query<Entity, With<Player>>,
+    factions: Factions,
+    mut stats: ResMut<MatchStats>,
+    mut commands: Commands,
+) {
+    let all_allied = |players: &[Entity]| {
+        players
+            .iter()
+            .all(|player| factions.shares_with(players[0], *player))
+    };
+
+    // Without an opponent there is nobody to win against.
+    let players: Vec<Entity> = players.iter().collect();
+    if stats.ended || all_allied(&players) {
+        return;
+    }
+
+    let main_buildings: Vec<(Entity, &BuildStatus)> = buildings
+        .iter()
+        .filter(|(building, ..)| {
+            matches!(building.building_type, BuildingType::MainBuilding { .. })
+        })
+        .filter_map(|(_, status, owner)| Some((owner.entity().ok()?, status)))
+        .collect();
+    let standing: Vec<Entity> = main_buildings
+        .iter()
+        .filter(|(_, status)| !matches!(status, BuildStatus::Destroyed))
+        .map(|(player, _)| *player)
+        .collect();
+    if main_buildings.is_empty() || !all_allied(&standing) {
+        return;
+    }
+
+    stats.ended = true;
+    let summary = stats.summary();
+
+    if let Err(error) = export(&summary) {
+        error!("Failed to export match statistics: {error}");
+    }
+
+    commands.server_trigger(ToClients {
+        mode: SendMode::Broadcast,
+        message: summary,
+    });
+}
+
+fn export(summary: &MatchSummary) -> std::io::Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    fs::create_dir_all(EXPORT_DIR)?;
+    let path = PathBuf::from(EXPORT_DIR).join(format!("match_{timestamp}.json"));
+    fs::write(&path, serde_json::to_string_pretty(summary)?)?;
+
+    info!("Exported match statistics to {}.", path.display());
+    Ok(())
+}
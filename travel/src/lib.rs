@@ -2,13 +2,15 @@ use bevy::prelude::*;
 
 use bevy::sprite::Anchor;
 use bevy_replicon::prelude::{AppRuleExt, ClientState, FromClient, Replicated};
+use console_protocol::BrpGameEventKind;
 use serde::{Deserialize, Serialize};
 use shared::{
     BoxCollider, ClientPlayerMap, ClientPlayerMapExt, ControlledPlayer, GameScene, GameSceneId,
-    PlayerState,
+    Player, PlayerState,
     map::Layers,
     server::{
         buildings::recruiting::{FlagAssignment, FlagHolder},
+        console::watch::WatchedEvents,
         entities::{Unit, commander::ArmyFlagAssignments},
         players::interaction::{ActiveInteraction, Interactable, InteractionType},
     },
@@ -29,6 +31,9 @@ impl Plugin for TravelPlugin {
             .add_observer(enter_travel_state)
             .add_observer(leave_travel_state)
             .add_observer(start_travel)
+            .add_observer(travel_with_army)
+            .add_observer(watch_travel_start)
+            .add_observer(watch_travel_end)
             .add_systems(
                 Update,
                 travel_timer.run_if(in_state(ClientState::Disconnected)),
@@ -40,6 +45,15 @@ impl Plugin for TravelPlugin {
     }
 }
 
+/// Sends a player from the scene end `source` to `target`, together with the flag they hold and
+/// its army.
+#[derive(Event, Clone, Copy)]
+pub struct StartTravel {
+    pub player: Entity,
+    pub source: GameScene,
+    pub target: GameScene,
+}
+
 #[derive(Component, Serialize, Deserialize)]
 pub struct Traveling {
     source: GameScene,
@@ -48,7 +62,7 @@ pub struct Traveling {
 }
 
 impl Traveling {
-    fn between(source: GameScene, target: GameScene) -> Self {
+    pub fn between(source: GameScene, target: GameScene) -> Self {
         Self {
             source,
             target,
@@ -125,21 +139,37 @@ fn travel_timer(mut query: Query<&mut Traveling>, time: Res<Time>) {
 
 fn start_travel(
     trigger: On<FromClient<SelectTravelDestination>>,
-    flag_holders: Query<Option<&FlagHolder>>,
-    commanders: Query<(&FlagAssignment, &ArmyFlagAssignments)>,
-    units_on_flag: Query<(Entity, &FlagAssignment, &Unit)>,
     interaction: Query<&ActiveInteraction>,
     game_scenes: Query<&GameScene>,
     client_player_map: Res<ClientPlayerMap>,
     mut commands: Commands,
 ) -> Result {
     let selection = &**trigger.event();
-    let player_entity = *client_player_map.get_player(&trigger.client_id)?;
+    let player = *client_player_map.get_player(&trigger.client_id)?;
 
-    let source = interaction.get(player_entity)?.interactable;
+    let source = interaction.get(player)?.interactable;
     let source = *game_scenes.get(source)?;
 
-    let target = **selection;
+    commands.trigger(StartTravel {
+        player,
+        source,
+        target: **selection,
+    });
+    Ok(())
+}
+
+fn travel_with_army(
+    trigger: On<StartTravel>,
+    flag_holders: Query<Option<&FlagHolder>>,
+    commanders: Query<(&FlagAssignment, &ArmyFlagAssignments)>,
+    units_on_flag: Query<(Entity, &FlagAssignment, &Unit)>,
+    mut commands: Commands,
+) -> Result {
+    let StartTravel {
+        player: player_entity,
+        source,
+        target,
+    } = *trigger;
 
     let flag_holder = flag_holders.get(player_entity)?;
 
@@ -256,3 +286,40 @@ fn leave_travel_state(
     next_state.set(PlayerState::World);
     Ok(())
 }
+
+fn watch_travel_start(
+    trigger: On<Add, Traveling>,
+    query: Query<(&Traveling, &Player)>,
+    watched: Option<ResMut<WatchedEvents>>,
+) {
+    let (Some(mut watched), Ok((travel, player))) = (watched, query.get(trigger.entity)) else {
+        return;
+    };
+
+    watched.push(
+        Some(&travel.source.id),
+        vec![player.id],
+        BrpGameEventKind::TravelStart {
+            from: travel.source.id.index(),
+            to: travel.target.id.index(),
+        },
+    );
+}
+
+fn watch_travel_end(
+    trigger: On<Remove, Traveling>,
+    query: Query<(&Traveling, &Player)>,
+    watched: Option<ResMut<WatchedEvents>>,
+) {
+    let (Some(mut watched), Ok((travel, player))) = (watched, query.get(trigger.entity)) else {
+        return;
+    };
+
+    watched.push(
+        Some(&travel.target.id),
+        vec![player.id],
+        BrpGameEventKind::TravelEnd {
+            scene: travel.target.id.index(),
+        },
+    );
+}